@@ -0,0 +1,26 @@
+//! `cargo bench [-- <day>]`: the same benchmarks as `aoc bench`, with the
+//! default number of runs.
+
+use std::env;
+use std::process::ExitCode;
+
+use advent_of_code_2023::bench::{bench_all, DEFAULT_RUNS};
+use advent_of_code_2023::runner;
+use advent_of_code_2023::workspace::Workspace;
+
+fn main() -> ExitCode {
+    let workspace = Workspace::from_env();
+    let day = env::args().skip(1).find_map(|arg| arg.parse().ok());
+
+    let results = runner::contain(|| bench_all(&workspace, day, DEFAULT_RUNS));
+    println!("{results}");
+
+    match results.write(&workspace.bench_results_path()) {
+        Ok(()) if results.failed.is_empty() => ExitCode::SUCCESS,
+        Ok(()) => ExitCode::FAILURE,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,304 @@
+//! The answer ledger: what we know about the real answer of every day and
+//! part, kept in `answers.toml` at the repository root.
+
+use core::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::registry::Solution;
+use crate::runner;
+use crate::workspace::Workspace;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    pub day: u8,
+    pub part: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected: Option<String>,
+    /// Answers that were submitted and rejected.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub wrong: Vec<String>,
+    /// The lowest rejected answer the server called too high.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub too_high: Option<String>,
+    /// The highest rejected answer the server called too low.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub too_low: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State<'a> {
+    Unknown,
+    Expected(&'a str),
+    /// Not solved yet, but these answers are known not to be it.
+    WrongGuesses(&'a [String]),
+}
+
+impl Entry {
+    pub fn state(&self) -> State<'_> {
+        match (&self.expected, self.wrong.is_empty()) {
+            (Some(expected), _) => State::Expected(expected),
+            (None, false) => State::WrongGuesses(&self.wrong),
+            (None, true) => State::Unknown,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Ledger {
+    #[serde(default, rename = "answer")]
+    entries: Vec<Entry>,
+}
+
+#[derive(Debug)]
+pub enum LedgerError {
+    Io(PathBuf, io::Error),
+    Toml(PathBuf, toml::de::Error),
+}
+
+impl fmt::Display for LedgerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LedgerError::Io(path, error) => write!(f, "{}: {error}", path.display()),
+            LedgerError::Toml(path, error) => write!(f, "{}: {error}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for LedgerError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail {
+        expected: String,
+    },
+    /// The answer was already submitted and rejected.
+    KnownWrong,
+    Unknown,
+    /// The solution panicked, so there is no answer to check.
+    Panicked(String),
+}
+
+impl Verdict {
+    pub fn is_failure(&self) -> bool {
+        matches!(
+            self,
+            Verdict::Fail { .. } | Verdict::KnownWrong | Verdict::Panicked(_)
+        )
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "pass"),
+            Verdict::Fail { expected } => write!(f, "FAIL (expected {expected})"),
+            Verdict::KnownWrong => write!(f, "FAIL (known wrong answer)"),
+            Verdict::Unknown => write!(f, "unknown"),
+            Verdict::Panicked(message) => write!(f, "PANIC: {message}"),
+        }
+    }
+}
+
+impl Ledger {
+    pub fn load(workspace: &Workspace) -> Result<Ledger, LedgerError> {
+        let path = workspace.answers_path();
+
+        match fs::read_to_string(&path) {
+            Ok(text) => toml::from_str(&text).map_err(|error| LedgerError::Toml(path, error)),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Ledger::default()),
+            Err(error) => Err(LedgerError::Io(path, error)),
+        }
+    }
+
+    pub fn save(&self, workspace: &Workspace) -> Result<(), LedgerError> {
+        let path = workspace.answers_path();
+        let text = toml::to_string(self).expect("ledger should serialize");
+
+        fs::write(&path, text).map_err(|error| LedgerError::Io(path, error))
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    pub fn entry(&self, day: u8, part: u8) -> Option<&Entry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.day == day && entry.part == part)
+    }
+
+    /// The entry for `day` and `part`, added in order if it is missing.
+    pub fn entry_mut(&mut self, day: u8, part: u8) -> &mut Entry {
+        let index = match self
+            .entries
+            .binary_search_by_key(&(day, part), |entry| (entry.day, entry.part))
+        {
+            Ok(index) => index,
+            Err(index) => {
+                let entry = Entry {
+                    day,
+                    part,
+                    expected: None,
+                    wrong: Vec::new(),
+                    too_high: None,
+                    too_low: None,
+                };
+                self.entries.insert(index, entry);
+                index
+            }
+        };
+
+        &mut self.entries[index]
+    }
+
+    pub fn state(&self, day: u8, part: u8) -> State<'_> {
+        self.entry(day, part).map_or(State::Unknown, Entry::state)
+    }
+
+    pub fn check(&self, day: u8, part: u8, answer: &str) -> Verdict {
+        match self.state(day, part) {
+            State::Expected(expected) if expected == answer => Verdict::Pass,
+            State::Expected(expected) => Verdict::Fail {
+                expected: expected.to_string(),
+            },
+            State::WrongGuesses(wrong) if wrong.iter().any(|x| x == answer) => Verdict::KnownWrong,
+            State::WrongGuesses(_) | State::Unknown => Verdict::Unknown,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub day: u8,
+    pub part: u8,
+    /// `None` when the day has no input to run on or the solution panicked.
+    pub answer: Option<String>,
+    pub verdict: Verdict,
+}
+
+/// Runs `solution` on its day's input and checks the answer against the
+/// ledger. A panic is reported as the verdict.
+pub fn verify(workspace: &Workspace, ledger: &Ledger, solution: &Solution) -> Check {
+    let input = fs::read_to_string(workspace.input_path(solution.day))
+        .ok()
+        .filter(|input| !input.trim().is_empty());
+
+    let (answer, verdict) = match input.map(|input| runner::catch(|| (solution.run)(&input))) {
+        Some(Ok(answer)) => {
+            let verdict = ledger.check(solution.day, solution.part, &answer);
+            (Some(answer), verdict)
+        }
+        Some(Err(message)) => (None, Verdict::Panicked(message)),
+        None => (None, Verdict::Unknown),
+    };
+
+    Check {
+        day: solution.day,
+        part: solution.part,
+        answer,
+        verdict,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::{self, Timed};
+    use crate::scaffold::tests::scratch_dir;
+    use rstest::*;
+
+    fn ledger() -> Ledger {
+        let mut ledger = Ledger::default();
+        ledger.entry_mut(2, 1).expected = Some("42".to_string());
+        ledger.entry_mut(1, 2).wrong = vec!["7".to_string(), "8".to_string()];
+        ledger
+    }
+
+    #[rstest]
+    #[case(2, 1, "42", Verdict::Pass)]
+    #[case(2, 1, "41", Verdict::Fail { expected: "42".to_string() })]
+    #[case(1, 2, "8", Verdict::KnownWrong)]
+    #[case(1, 2, "9", Verdict::Unknown)]
+    #[case(3, 1, "1", Verdict::Unknown)]
+    fn check(#[case] day: u8, #[case] part: u8, #[case] answer: &str, #[case] expected: Verdict) {
+        assert_eq!(ledger().check(day, part, answer), expected);
+    }
+
+    #[test]
+    fn entries_stay_sorted() {
+        let ledger = ledger();
+
+        let keys: Vec<(u8, u8)> = ledger.entries().iter().map(|x| (x.day, x.part)).collect();
+
+        assert_eq!(keys, vec![(1, 2), (2, 1)]);
+        assert_eq!(
+            toml::from_str::<Ledger>(&toml::to_string(&ledger).unwrap()).unwrap(),
+            ledger
+        );
+    }
+
+    fn explode(input: &str) -> String {
+        panic!("no solution for {}", input.trim())
+    }
+
+    #[test]
+    fn panics_fail_verification() {
+        let workspace = Workspace::new(scratch_dir("verify-panic"));
+        fs::create_dir_all(workspace.day_dir(2)).unwrap();
+        fs::write(workspace.input_path(2), "42\n").unwrap();
+        let solution = Solution {
+            day: 2,
+            part: 1,
+            run: explode,
+            timed: |input| Timed::whole(input, explode),
+        };
+
+        let check = verify(&workspace, &ledger(), &solution);
+
+        assert_eq!(check.answer, None);
+        assert_eq!(
+            check.verdict,
+            Verdict::Panicked("no solution for 42".to_string())
+        );
+        assert!(check.verdict.is_failure());
+    }
+
+    /// Every answer in the ledger, checked against the real inputs.
+    #[test]
+    fn ledger_answers() {
+        let workspace = Workspace::from_env();
+        let ledger = Ledger::load(&workspace).unwrap();
+
+        let failures: Vec<String> = ledger
+            .entries()
+            .iter()
+            .filter(|entry| entry.state() != State::Unknown)
+            .filter_map(|entry| {
+                let Some(solution) = registry::find(entry.day, entry.part) else {
+                    return Some(format!(
+                        "day {} part {}: not registered",
+                        entry.day, entry.part
+                    ));
+                };
+                let check = verify(&workspace, &ledger, solution);
+
+                check.verdict.is_failure().then(|| {
+                    format!(
+                        "day {} part {}: {}, got {}",
+                        entry.day,
+                        entry.part,
+                        check.verdict,
+                        check.answer.unwrap_or_default()
+                    )
+                })
+            })
+            .collect();
+
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+}
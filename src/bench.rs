@@ -0,0 +1,294 @@
+//! Timing of every registered solution on its real input, run from
+//! `aoc bench` and `cargo bench`.
+
+use core::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::registry::{Solution, Timed, SOLUTIONS};
+use crate::runner;
+use crate::workspace::Workspace;
+
+pub const DEFAULT_RUNS: usize = 10;
+
+/// Summary of a set of timings, in nanoseconds so results files diff cleanly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stats {
+    pub median: u64,
+    pub min: u64,
+    pub max: u64,
+}
+
+impl Stats {
+    /// `None` for no samples.
+    pub fn new(samples: impl IntoIterator<Item = Duration>) -> Option<Stats> {
+        let mut nanos: Vec<u64> = samples
+            .into_iter()
+            .map(|x| u64::try_from(x.as_nanos()).unwrap_or(u64::MAX))
+            .collect();
+        nanos.sort_unstable();
+
+        let median = match nanos.len() {
+            0 => return None,
+            n if n % 2 == 1 => nanos[n / 2],
+            n => (nanos[n / 2 - 1] + nanos[n / 2]) / 2,
+        };
+
+        Some(Stats {
+            median,
+            min: nanos[0],
+            max: nanos[nanos.len() - 1],
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bench {
+    pub day: u8,
+    pub part: u8,
+    pub runs: usize,
+    pub answer: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parse: Option<Stats>,
+    pub solve: Stats,
+    pub total: Stats,
+}
+
+/// A solution that panicked instead of finishing its runs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Failed {
+    pub day: u8,
+    pub part: u8,
+    pub error: String,
+}
+
+/// What `aoc bench` writes, one entry per solution in registry order.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Results {
+    #[serde(default, rename = "bench")]
+    pub benches: Vec<Bench>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failed: Vec<Failed>,
+}
+
+#[derive(Debug)]
+pub enum BenchError {
+    Io(PathBuf, io::Error),
+}
+
+impl fmt::Display for BenchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BenchError::Io(path, error) => write!(f, "{}: {error}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for BenchError {}
+
+/// Runs `solution` on `input` `runs` times, which must be at least once.
+pub fn bench(solution: &Solution, input: &str, runs: usize) -> Bench {
+    assert!(runs > 0, "a benchmark needs at least one run");
+
+    let timings: Vec<Timed> = (0..runs).map(|_| (solution.timed)(input)).collect();
+    let stats = |select: fn(&Timed) -> Duration| {
+        Stats::new(timings.iter().map(select)).expect("there is at least one run")
+    };
+
+    Bench {
+        day: solution.day,
+        part: solution.part,
+        runs,
+        answer: timings[0].answer.clone(),
+        parse: Stats::new(timings.iter().filter_map(|x| x.parse)),
+        solve: stats(|x| x.solve),
+        total: stats(Timed::total),
+    }
+}
+
+/// Benchmarks every registered solution of `day`, or of every day, skipping
+/// days without an input. A solution that panics is recorded as failed and
+/// the rest are still benchmarked.
+pub fn bench_all(workspace: &Workspace, day: Option<u8>, runs: usize) -> Results {
+    bench_each(workspace, SOLUTIONS, day, runs)
+}
+
+fn bench_each(
+    workspace: &Workspace,
+    solutions: &[Solution],
+    day: Option<u8>,
+    runs: usize,
+) -> Results {
+    let mut results = Results::default();
+
+    for solution in solutions {
+        if day.is_some_and(|day| day != solution.day) {
+            continue;
+        }
+        let input = match fs::read_to_string(workspace.input_path(solution.day)) {
+            Ok(input) if !input.trim().is_empty() => input,
+            _ => continue,
+        };
+
+        match runner::catch(|| bench(solution, &input, runs)) {
+            Ok(bench) => results.benches.push(bench),
+            Err(error) => results.failed.push(Failed {
+                day: solution.day,
+                part: solution.part,
+                error,
+            }),
+        }
+    }
+
+    results
+}
+
+impl Results {
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).expect("results should serialize")
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), BenchError> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|error| BenchError::Io(dir.to_path_buf(), error))?;
+        }
+
+        fs::write(path, self.to_toml()).map_err(|error| BenchError::Io(path.to_path_buf(), error))
+    }
+
+    /// Sum of the median total times.
+    pub fn total(&self) -> Duration {
+        self.benches
+            .iter()
+            .map(|bench| Duration::from_nanos(bench.total.median))
+            .sum()
+    }
+}
+
+fn format_nanos(nanos: u64) -> String {
+    format!("{:.3?}", Duration::from_nanos(nanos))
+}
+
+impl fmt::Display for Results {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>3} {:>4}  {:>10}  {:>10} {:>10} {:>10}  {:>10} {:>10} {:>10}",
+            "day", "part", "parse", "solve", "min", "max", "total", "min", "max"
+        )?;
+
+        for bench in &self.benches {
+            writeln!(
+                f,
+                "{:>3} {:>4}  {:>10}  {:>10} {:>10} {:>10}  {:>10} {:>10} {:>10}",
+                bench.day,
+                bench.part,
+                bench
+                    .parse
+                    .map_or("-".to_string(), |parse| format_nanos(parse.median)),
+                format_nanos(bench.solve.median),
+                format_nanos(bench.solve.min),
+                format_nanos(bench.solve.max),
+                format_nanos(bench.total.median),
+                format_nanos(bench.total.min),
+                format_nanos(bench.total.max),
+            )?;
+        }
+        for failed in &self.failed {
+            writeln!(
+                f,
+                "{:>3} {:>4}  PANIC: {}",
+                failed.day, failed.part, failed.error
+            )?;
+        }
+
+        write!(f, "total (sum of medians): {:.3?}", self.total())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+    use crate::scaffold::tests::scratch_dir;
+    use rstest::*;
+
+    #[rstest]
+    #[case(&[3, 1, 2], Some((2, 1, 3)))]
+    #[case(&[4, 1, 2, 10], Some((3, 1, 10)))]
+    #[case(&[7], Some((7, 7, 7)))]
+    #[case(&[], None)]
+    fn stats(#[case] nanos: &[u64], #[case] expected: Option<(u64, u64, u64)>) {
+        let stats = Stats::new(nanos.iter().map(|x| Duration::from_nanos(*x)));
+
+        assert_eq!(stats.map(|x| (x.median, x.min, x.max)), expected);
+    }
+
+    fn answer(input: &str) -> String {
+        input.trim().to_string()
+    }
+
+    fn explode(input: &str) -> String {
+        panic!("no solution for {}", input.trim())
+    }
+
+    const SOLUTIONS: &[Solution] = &[
+        Solution {
+            day: 1,
+            part: 1,
+            run: explode,
+            timed: |input| Timed::whole(input, explode),
+        },
+        Solution {
+            day: 1,
+            part: 2,
+            run: answer,
+            timed: |input| Timed::whole(input, answer),
+        },
+    ];
+
+    #[test]
+    fn panics_are_recorded() {
+        let workspace = Workspace::new(scratch_dir("bench-panic"));
+        fs::create_dir_all(workspace.day_dir(1)).unwrap();
+        fs::write(workspace.input_path(1), "42\n").unwrap();
+
+        let results = bench_each(&workspace, SOLUTIONS, None, 2);
+
+        let answers: Vec<_> = results.benches.iter().map(|x| x.answer.as_str()).collect();
+        assert_eq!(answers, vec!["42"]);
+        assert_eq!(
+            results.failed,
+            vec![Failed {
+                day: 1,
+                part: 1,
+                error: "no solution for 42".to_string(),
+            }]
+        );
+        assert_eq!(
+            toml::from_str::<Results>(&results.to_toml()).unwrap(),
+            results
+        );
+        assert!(results
+            .to_string()
+            .contains("  1    1  PANIC: no solution for 42"));
+    }
+
+    #[rstest]
+    #[case(1, 1, false)]
+    #[case(2, 1, true)]
+    fn bench_stages(#[case] day: u8, #[case] part: u8, #[case] parses: bool) {
+        let solution = registry::find(day, part).unwrap();
+        let input = "Game 1: 3 blue, 4 red\n";
+
+        let bench = bench(solution, input, 3);
+
+        assert_eq!(bench.runs, 3);
+        assert_eq!(bench.parse.is_some(), parses);
+        assert!(bench.solve.min <= bench.solve.median && bench.solve.median <= bench.solve.max);
+    }
+}
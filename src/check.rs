@@ -0,0 +1,194 @@
+//! `aoc check <day>`: one day's solutions on its examples and its real input,
+//! each answer next to what it should be. `aoc watch` reruns it on every
+//! change.
+
+use core::fmt;
+use std::fs;
+
+use crate::answers::{Ledger, Verdict};
+use crate::examples::{Examples, ExamplesError};
+use crate::registry;
+use crate::runner::{self, Status};
+use crate::workspace::Workspace;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Case {
+    pub part: u8,
+    /// `example N` or `input`.
+    pub name: String,
+    /// `None` unless the solution ran to completion.
+    pub answer: Option<String>,
+    pub status: Status,
+    /// An example of a part `examples.toml` lists as not solved yet.
+    pub pending: bool,
+}
+
+impl Case {
+    pub fn is_failure(&self) -> bool {
+        !self.pending && self.status.is_failure()
+    }
+}
+
+fn run(run: fn(&str) -> String, input: &str) -> Result<String, Status> {
+    runner::catch(|| run(input)).map_err(Status::Panicked)
+}
+
+/// Runs every registered part of `day` on its examples, then on the real
+/// input, checking those against the ledger.
+pub fn check_day(
+    workspace: &Workspace,
+    ledger: &Ledger,
+    day: u8,
+) -> Result<Vec<Case>, ExamplesError> {
+    let Examples { pending, examples } = Examples::load(workspace, day)?;
+    let input = fs::read_to_string(workspace.input_path(day))
+        .ok()
+        .filter(|input| !input.trim().is_empty());
+
+    let mut cases = Vec::new();
+    for solution in registry::for_day(day) {
+        let part = solution.part;
+
+        let part_examples = examples.iter().filter(|example| example.part == part);
+        for (number, example) in (1..).zip(part_examples) {
+            let (answer, status) = match run(solution.run, &example.input) {
+                Ok(answer) if answer == example.answer => {
+                    (Some(answer), Status::Checked(Verdict::Pass))
+                }
+                Ok(answer) => {
+                    let expected = example.answer.clone();
+                    (Some(answer), Status::Checked(Verdict::Fail { expected }))
+                }
+                Err(status) => (None, status),
+            };
+
+            cases.push(Case {
+                part,
+                name: format!("example {number}"),
+                answer,
+                status,
+                pending: pending.contains(&part),
+            });
+        }
+
+        let (answer, status) = match input.as_deref().map(|input| run(solution.run, input)) {
+            Some(Ok(answer)) => {
+                let verdict = ledger.check(day, part, &answer);
+                (Some(answer), Status::Checked(verdict))
+            }
+            Some(Err(status)) => (None, status),
+            None => (None, Status::NoInput),
+        };
+
+        cases.push(Case {
+            part,
+            name: "input".to_string(),
+            answer,
+            status,
+            pending: false,
+        });
+    }
+
+    Ok(cases)
+}
+
+/// The cases of a check, one line each.
+pub struct Report<'a>(pub &'a [Case]);
+
+impl fmt::Display for Report<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let answer_width = self
+            .0
+            .iter()
+            .filter_map(|case| case.answer.as_ref())
+            .map(String::len)
+            .max()
+            .unwrap_or(0)
+            .max(1);
+
+        for case in self.0 {
+            write!(
+                f,
+                "part {}  {:<10}  {:<answer_width$}  {}",
+                case.part,
+                case.name,
+                case.answer.as_deref().unwrap_or("-"),
+                case.status
+            )?;
+            if case.pending {
+                write!(f, " (pending)")?;
+            }
+            writeln!(f)?;
+        }
+
+        let failures = self.0.iter().filter(|case| case.is_failure()).count();
+        write!(f, "{} cases, {failures} failed", self.0.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scaffold::tests::scratch_dir;
+
+    #[test]
+    fn examples_and_input() {
+        let workspace = Workspace::new(scratch_dir("check"));
+        fs::create_dir_all(workspace.day_dir(1)).unwrap();
+        fs::write(
+            workspace.examples_path(1),
+            r#"pending = [2]
+
+[[example]]
+part = 1
+input = "1abc2\npqr3stu8vwx"
+answer = "50"
+
+[[example]]
+part = 1
+input = "1abc2"
+answer = "13"
+
+[[example]]
+part = 2
+input = "two1nine"
+answer = "0"
+"#,
+        )
+        .unwrap();
+        fs::write(workspace.input_path(1), "a1b2c3d4e5f\n").unwrap();
+        let mut ledger = Ledger::default();
+        ledger.entry_mut(1, 1).expected = Some("15".to_string());
+
+        let cases = check_day(&workspace, &ledger, 1).unwrap();
+
+        let summary: Vec<_> = cases
+            .iter()
+            .map(|case| {
+                (
+                    case.part,
+                    case.name.as_str(),
+                    case.answer.as_deref(),
+                    case.is_failure(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (1, "example 1", Some("50"), false),
+                (1, "example 2", Some("12"), true),
+                (1, "input", Some("15"), false),
+                (2, "example 1", Some("29"), false),
+                (2, "input", Some("15"), false),
+            ]
+        );
+        assert_eq!(
+            cases[1].status,
+            Status::Checked(Verdict::Fail {
+                expected: "13".to_string()
+            })
+        );
+        assert!(Report(&cases).to_string().ends_with("5 cases, 1 failed"));
+    }
+}
@@ -0,0 +1,457 @@
+use std::fmt::Display;
+use std::io;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use crate::answers::{self, Ledger};
+use crate::bench::{self, DEFAULT_RUNS};
+use crate::check::{self, Report};
+use crate::client::{self, Client, Config, Fetched};
+use crate::examples;
+use crate::registry;
+use crate::report::{self, Format};
+use crate::runner::{self, Summary};
+use crate::scaffold;
+use crate::submit;
+use crate::trace;
+use crate::watch;
+use crate::workspace::Workspace;
+
+const USAGE: &str = "usage: aoc [--log <filter>] <command>
+    aoc new <day>         create src/dayN from the day0 template and register it
+    aoc examples <day>    extract the README examples into src/dayN/examples.toml
+    aoc examples <day> --print
+                          show the extracted examples without writing them
+    aoc fetch <day>       download the day's input into inputs/dayN.txt,
+                          unless the day already has one
+    aoc readme <day>      download the puzzle text into src/dayN/README.md
+    aoc submit <day> <part>
+                          run the solution on the real input and send its
+                          answer, unless answers.toml already knows the verdict
+    aoc check <day>       run the day on its examples and real input and show
+                          how the answers compare to the expected ones
+    aoc watch <day> [--interval <ms>]
+                          rebuild and rerun `aoc check <day>` whenever
+                          something in src/dayN or the day's input changes
+    aoc verify [--day <day>]
+                          run the solutions on the real inputs and check them
+                          against answers.toml
+    aoc bench [--day <day>] [--runs <n>] [--out <file>]
+                          time parsing and solving on the real inputs and
+                          write the results to target/bench/results.toml
+    aoc run --all [--threads <n>] [--format table|json|junit]
+                          run every solution in parallel and summarize them,
+                          or report them as JSON lines or JUnit XML
+
+--log (or AOC_LOG) shows the solutions' debug output, e.g. `debug` or
+`warn,day12=trace`.
+
+The puzzle server is reached with the session token in AOC_SESSION and at
+AOC_BASE_URL, or `session` and `base_url` in aoc.toml. https:// URLs, like the
+default https://adventofcode.com, are requested with `curl`, which must be
+installed; AOC_HTTP (or `http` in aoc.toml) set to `plain` or `curl` picks the
+backend instead.";
+
+fn parse_day(day: &str) -> Result<u8, String> {
+    day.parse()
+        .map_err(|_| format!("expected a day number, got {day:?}"))
+}
+
+fn fail(error: impl Display) -> i32 {
+    eprintln!("error: {error}");
+    1
+}
+
+/// `--name value` pairs following a subcommand, in any order.
+#[derive(Debug, PartialEq, Eq)]
+struct Options<'a> {
+    values: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> Options<'a> {
+    fn parse(args: &[&'a str], names: &[&str]) -> Result<Self, String> {
+        let mut values = Vec::new();
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            let name = arg
+                .strip_prefix("--")
+                .filter(|name| names.contains(name))
+                .ok_or_else(|| format!("unexpected argument {arg:?}"))?;
+            let value = args
+                .next()
+                .ok_or_else(|| format!("--{name} needs a value"))?;
+
+            values.push((name, *value));
+        }
+
+        Ok(Options { values })
+    }
+
+    fn get(&self, name: &str) -> Option<&'a str> {
+        self.values
+            .iter()
+            .rev()
+            .find_map(|(key, value)| (*key == name).then_some(*value))
+    }
+
+    fn day(&self) -> Result<Option<u8>, String> {
+        self.get("day").map(parse_day).transpose()
+    }
+}
+
+fn fetch(workspace: &Workspace, day: u8) -> i32 {
+    let client = match Config::load(workspace) {
+        Ok(config) => Client::from_config(config),
+        Err(error) => return fail(error),
+    };
+
+    match client::fetch_input(workspace, &client, day) {
+        Ok(Fetched::Downloaded(path)) => {
+            println!("wrote {}", path.display());
+            0
+        }
+        Ok(Fetched::Cached(path)) => {
+            println!("{} is already there", path.display());
+            0
+        }
+        Err(error) => fail(error),
+    }
+}
+
+fn readme(workspace: &Workspace, day: u8) -> i32 {
+    let client = match Config::load(workspace) {
+        Ok(config) => Client::from_config(config),
+        Err(error) => return fail(error),
+    };
+
+    match client::fetch_readme(workspace, &client, day) {
+        Ok(path) => {
+            println!(
+                "wrote {}, `aoc examples {day}` extracts its examples",
+                path.display()
+            );
+            0
+        }
+        Err(error) => fail(error),
+    }
+}
+
+fn submit(workspace: &Workspace, day: u8, part: u8) -> i32 {
+    let Some(solution) = registry::find(day, part) else {
+        return fail(format!("day {day} part {part} is not registered"));
+    };
+    let client = match Config::load(workspace) {
+        Ok(config) => Client::from_config(config),
+        Err(error) => return fail(error),
+    };
+    let mut ledger = match Ledger::load(workspace) {
+        Ok(ledger) => ledger,
+        Err(error) => return fail(error),
+    };
+
+    // A panic is reported as a failed run, not as it happens.
+    let answer = runner::contain(|| submit::run_solution(workspace, solution));
+
+    let answer = match answer {
+        Ok(answer) => answer,
+        Err(error) => return fail(error),
+    };
+    println!("day {day} part {part}: {answer}");
+
+    let now = SystemTime::now();
+    match submit::submit(workspace, &mut ledger, &client, (day, part), &answer, now) {
+        Ok(reply) => {
+            println!("{reply}");
+            i32::from(reply != submit::Reply::Right)
+        }
+        Err(error) => fail(error),
+    }
+}
+
+fn check(workspace: &Workspace, day: u8) -> i32 {
+    let ledger = match Ledger::load(workspace) {
+        Ok(ledger) => ledger,
+        Err(error) => return fail(error),
+    };
+
+    // Panics are reported with their case, not as they happen.
+    let cases = runner::contain(|| check::check_day(workspace, &ledger, day));
+
+    match cases {
+        Ok(cases) => {
+            println!("{}", Report(&cases));
+            i32::from(cases.iter().any(check::Case::is_failure))
+        }
+        Err(error) => fail(error),
+    }
+}
+
+fn watch(workspace: &Workspace, day: u8, options: &[&str]) -> i32 {
+    let interval =
+        Options::parse(options, &["interval"]).and_then(|options| match options.get("interval") {
+            Some(interval) => interval
+                .parse()
+                .ok()
+                .filter(|interval| *interval > 0)
+                .map(Duration::from_millis)
+                .ok_or_else(|| {
+                    format!("expected a positive number of milliseconds, got {interval:?}")
+                }),
+            None => Ok(watch::DEFAULT_INTERVAL),
+        });
+    let interval = match interval {
+        Ok(interval) => interval,
+        Err(error) => return fail(error),
+    };
+
+    watch::watch(workspace, day, interval, |changed| {
+        let names: Vec<String> = changed
+            .iter()
+            .map(|path| {
+                path.strip_prefix(workspace.root())
+                    .unwrap_or(path)
+                    .display()
+                    .to_string()
+            })
+            .collect();
+        if names.is_empty() {
+            println!("--- watching {} ---", workspace.day_dir(day).display());
+        } else {
+            println!("--- {} changed ---", names.join(", "));
+        }
+
+        if let Err(error) = watch::run_check(workspace, day) {
+            eprintln!("error: cargo run: {error}");
+        }
+    })
+}
+
+fn verify(workspace: &Workspace, options: &[&str]) -> i32 {
+    let day = match Options::parse(options, &["day"]).and_then(|options| options.day()) {
+        Ok(day) => day,
+        Err(error) => return fail(error),
+    };
+    let ledger = match Ledger::load(workspace) {
+        Ok(ledger) => ledger,
+        Err(error) => return fail(error),
+    };
+
+    let mut failed = false;
+    for solution in registry::SOLUTIONS {
+        if day.is_some_and(|day| day != solution.day) {
+            continue;
+        }
+
+        // A panic is reported as the verdict, not as it happens.
+        let check = runner::contain(|| answers::verify(workspace, &ledger, solution));
+        let answer = check.answer.as_deref().unwrap_or("(no input)");
+        println!(
+            "day {:>2} part {}  {:<16}  {}",
+            check.day, check.part, answer, check.verdict
+        );
+        failed |= check.verdict.is_failure();
+    }
+
+    i32::from(failed)
+}
+
+fn bench(workspace: &Workspace, options: &[&str]) -> i32 {
+    let parsed = Options::parse(options, &["day", "runs", "out"]).and_then(|options| {
+        let runs = match options.get("runs") {
+            Some(runs) => runs
+                .parse()
+                .ok()
+                .filter(|runs| *runs > 0)
+                .ok_or_else(|| format!("expected a positive number of runs, got {runs:?}"))?,
+            None => DEFAULT_RUNS,
+        };
+        let out = options
+            .get("out")
+            .map_or_else(|| workspace.bench_results_path(), PathBuf::from);
+
+        Ok((options.day()?, runs, out))
+    });
+    let (day, runs, out) = match parsed {
+        Ok(parsed) => parsed,
+        Err(error) => return fail(error),
+    };
+
+    // Panics are reported with the results, not as they happen.
+    let results = runner::contain(|| bench::bench_all(workspace, day, runs));
+    println!("{results}");
+
+    match results.write(&out) {
+        Ok(()) => {
+            println!("wrote {}", out.display());
+            i32::from(!results.failed.is_empty())
+        }
+        Err(error) => fail(error),
+    }
+}
+
+fn run_all(workspace: &Workspace, options: &[&str]) -> i32 {
+    let parsed = Options::parse(options, &["threads", "format"]).and_then(|options| {
+        let threads = match options.get("threads") {
+            Some(threads) => threads
+                .parse()
+                .ok()
+                .filter(|threads| *threads > 0)
+                .ok_or_else(|| format!("expected a positive number of threads, got {threads:?}"))?,
+            None => runner::default_threads(),
+        };
+        let format = options
+            .get("format")
+            .map_or(Ok(Format::Table), str::parse)?;
+
+        Ok((threads, format))
+    });
+    let (threads, format) = match parsed {
+        Ok(parsed) => parsed,
+        Err(error) => return fail(error),
+    };
+    let ledger = match Ledger::load(workspace) {
+        Ok(ledger) => ledger,
+        Err(error) => return fail(error),
+    };
+
+    // Panics are reported in the summary, not as they happen.
+    let outcomes =
+        runner::contain(|| runner::run_all(workspace, &ledger, registry::SOLUTIONS, threads));
+
+    let written = match format {
+        Format::Table => {
+            println!("{}", Summary(&outcomes));
+            Ok(())
+        }
+        Format::Json => report::write_json(&mut io::stdout().lock(), &outcomes),
+        Format::Junit => report::write_junit(&mut io::stdout().lock(), &outcomes),
+    };
+    if let Err(error) = written {
+        return fail(error);
+    }
+
+    i32::from(outcomes.iter().any(|outcome| outcome.status.is_failure()))
+}
+
+/// Runs the `aoc` command line and returns its exit code.
+pub fn run(args: impl IntoIterator<Item = String>) -> i32 {
+    let args: Vec<String> = args.into_iter().collect();
+    let mut args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    let log = match args.iter().position(|arg| *arg == "--log") {
+        Some(index) if index + 1 < args.len() => {
+            let spec = args.remove(index + 1);
+            args.remove(index);
+            spec.parse().map(trace::set_filter)
+        }
+        Some(_) => Err("--log needs a filter".to_string()),
+        None => trace::init_from_env(),
+    };
+    if let Err(error) = log {
+        return fail(error);
+    }
+
+    let workspace = Workspace::from_env();
+
+    match args[..] {
+        ["new", day] => {
+            let day = match parse_day(day) {
+                Ok(day) => day,
+                Err(error) => return fail(error),
+            };
+
+            match scaffold::new_day(&workspace, day) {
+                Ok(day_dir) => {
+                    println!("created {}", day_dir.display());
+                    0
+                }
+                Err(error) => fail(error),
+            }
+        }
+        ["examples", day] => {
+            let day = match parse_day(day) {
+                Ok(day) => day,
+                Err(error) => return fail(error),
+            };
+
+            match examples::update_examples(&workspace, day) {
+                Ok(examples) => {
+                    println!(
+                        "wrote {} examples to {}",
+                        examples.examples.len(),
+                        workspace.examples_path(day).display()
+                    );
+                    0
+                }
+                Err(error) => fail(error),
+            }
+        }
+        ["examples", day, "--print"] => {
+            let day = match parse_day(day) {
+                Ok(day) => day,
+                Err(error) => return fail(error),
+            };
+
+            match examples::read_readme(&workspace, day) {
+                Ok(puzzle) => {
+                    println!("# Day {}: {}", puzzle.day, puzzle.title);
+                    print!("{}", examples::Examples::from(&puzzle).to_toml());
+                    0
+                }
+                Err(error) => fail(error),
+            }
+        }
+        ["fetch", day] => match parse_day(day) {
+            Ok(day) => fetch(&workspace, day),
+            Err(error) => fail(error),
+        },
+        ["readme", day] => match parse_day(day) {
+            Ok(day) => readme(&workspace, day),
+            Err(error) => fail(error),
+        },
+        ["submit", day, part] => match (parse_day(day), part.parse()) {
+            (Ok(day), Ok(part)) => submit(&workspace, day, part),
+            (Err(error), _) => fail(error),
+            (_, Err(_)) => fail(format!("expected a part number, got {part:?}")),
+        },
+        ["check", day] => match parse_day(day) {
+            Ok(day) => check(&workspace, day),
+            Err(error) => fail(error),
+        },
+        ["watch", day, ref options @ ..] => match parse_day(day) {
+            Ok(day) => watch(&workspace, day, options),
+            Err(error) => fail(error),
+        },
+        ["verify", ref options @ ..] => verify(&workspace, options),
+        ["bench", ref options @ ..] => bench(&workspace, options),
+        ["run", "--all", ref options @ ..] => run_all(&workspace, options),
+        _ => {
+            eprintln!("{USAGE}");
+            2
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[test]
+    fn options() {
+        let options = Options::parse(&["--runs", "3", "--day", "7"], &["day", "runs"]).unwrap();
+
+        assert_eq!(options.get("runs"), Some("3"));
+        assert_eq!(options.day(), Ok(Some(7)));
+        assert_eq!(options.get("out"), None);
+    }
+
+    #[rstest]
+    #[case(&["--day"])]
+    #[case(&["--out", "x"])]
+    #[case(&["7"])]
+    fn bad_options(#[case] args: &[&str]) {
+        assert!(Options::parse(args, &["day"]).is_err());
+    }
+}
@@ -0,0 +1,347 @@
+//! The puzzle server: who we are to it and what we ask of it.
+//!
+//! The session token, base URL and HTTP backend come from `AOC_SESSION`,
+//! `AOC_BASE_URL` and `AOC_HTTP`, or else from `aoc.toml` at the repository
+//! root, which is not committed:
+//!
+//! ```toml
+//! session = "53616c7465645f5f..."
+//! base_url = "http://localhost:8080"
+//! http = "plain"
+//! ```
+//!
+//! Without `http`, `http://` URLs are fetched over plain TCP and anything
+//! else with `curl`, which then has to be installed.
+
+use core::fmt;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use crate::html;
+use crate::http::{Backend, Http, HttpError, Reason, Request};
+use crate::workspace::Workspace;
+
+pub const YEAR: u16 = 2023;
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+pub struct Config {
+    pub session: Option<String>,
+    pub base_url: Option<String>,
+    pub http: Option<Backend>,
+}
+
+#[derive(Debug)]
+pub enum ClientError {
+    MissingSession,
+    /// `AOC_HTTP` names no backend.
+    Backend(String),
+    /// The server answered, but not with what we asked for.
+    Status(u16, String),
+    /// The day's page has no puzzle text in it.
+    NoPuzzle(u8),
+    Http(HttpError),
+    Io(PathBuf, io::Error),
+    Config(PathBuf, toml::de::Error),
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::MissingSession => {
+                write!(
+                    f,
+                    "no session token, set AOC_SESSION or `session` in aoc.toml"
+                )
+            }
+            ClientError::Backend(error) => write!(f, "AOC_HTTP: {error}"),
+            ClientError::Status(status, body) => {
+                write!(f, "server answered {status}: {}", body.trim())
+            }
+            ClientError::NoPuzzle(day) => write!(f, "found no puzzle on the page of day {day}"),
+            ClientError::Http(error) => write!(f, "{error}"),
+            ClientError::Io(path, error) => write!(f, "{}: {error}", path.display()),
+            ClientError::Config(path, error) => write!(f, "{}: {error}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<HttpError> for ClientError {
+    fn from(error: HttpError) -> Self {
+        ClientError::Http(error)
+    }
+}
+
+impl Config {
+    /// `aoc.toml`, if there is one, overridden by the environment.
+    pub fn load(workspace: &Workspace) -> Result<Config, ClientError> {
+        let path = workspace.config_path();
+
+        let mut config: Config = match fs::read_to_string(&path) {
+            Ok(text) => toml::from_str(&text).map_err(|error| ClientError::Config(path, error))?,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Config::default(),
+            Err(error) => return Err(ClientError::Io(path, error)),
+        };
+
+        if let Ok(session) = env::var("AOC_SESSION") {
+            config.session = Some(session);
+        }
+        if let Ok(base_url) = env::var("AOC_BASE_URL") {
+            config.base_url = Some(base_url);
+        }
+        if let Ok(http) = env::var("AOC_HTTP") {
+            config.http = Some(http.parse().map_err(ClientError::Backend)?);
+        }
+
+        Ok(config)
+    }
+}
+
+/// `answer` form-encoded.
+fn encode(answer: &str) -> String {
+    answer
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+pub struct Client {
+    base_url: String,
+    session: Option<String>,
+    http: Box<dyn Http>,
+}
+
+impl Client {
+    pub fn new(base_url: impl Into<String>, session: Option<String>, http: Box<dyn Http>) -> Self {
+        Client {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            session,
+            http,
+        }
+    }
+
+    /// A client for the configured server, over the configured backend or
+    /// else the one its URL needs.
+    pub fn from_config(config: Config) -> Self {
+        let base_url = config
+            .base_url
+            .unwrap_or_else(|| DEFAULT_BASE_URL.to_string());
+        let http = match config.http {
+            Some(backend) => backend.build(Reason::Configured),
+            None => Backend::for_url(&base_url).build(Reason::Url),
+        };
+
+        Client::new(base_url, config.session, http)
+    }
+
+    pub fn day_url(&self, day: u8) -> String {
+        format!("{}/{YEAR}/day/{day}", self.base_url)
+    }
+
+    /// Sends `request` as the logged in user and returns the body of a `200`.
+    fn send(&self, request: Request) -> Result<String, ClientError> {
+        let session = self.session.as_ref().ok_or(ClientError::MissingSession)?;
+        let request = request
+            .header("Cookie", format!("session={session}"))
+            .header("User-Agent", USER_AGENT);
+
+        let response = self.http.send(&request)?;
+        match response.status {
+            200 => Ok(response.body),
+            status => Err(ClientError::Status(status, response.body)),
+        }
+    }
+
+    /// The day's page, with part two once it is unlocked.
+    pub fn puzzle(&self, day: u8) -> Result<String, ClientError> {
+        self.send(Request::get(self.day_url(day)))
+    }
+
+    pub fn input(&self, day: u8) -> Result<String, ClientError> {
+        self.send(Request::get(format!("{}/input", self.day_url(day))))
+    }
+
+    /// Posts `answer` and returns the page the server answers with.
+    pub fn submit(&self, day: u8, part: u8, answer: &str) -> Result<String, ClientError> {
+        let form = format!("level={part}&answer={}", encode(answer));
+        self.send(Request::post(format!("{}/answer", self.day_url(day)), form))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fetched {
+    Downloaded(PathBuf),
+    /// The input was already there, so nothing was requested.
+    Cached(PathBuf),
+}
+
+/// Downloads the input of `day` into the inputs cache, unless the day already
+/// has an input, committed or cached. Inputs never change, so they are only
+/// asked for once.
+pub fn fetch_input(
+    workspace: &Workspace,
+    client: &Client,
+    day: u8,
+) -> Result<Fetched, ClientError> {
+    let existing = workspace.input_path(day);
+    if fs::read_to_string(&existing).is_ok_and(|input| !input.trim().is_empty()) {
+        return Ok(Fetched::Cached(existing));
+    }
+
+    let input = client.input(day)?;
+
+    let dir = workspace.inputs_dir();
+    fs::create_dir_all(&dir).map_err(|error| ClientError::Io(dir, error))?;
+    let path = workspace.cached_input_path(day);
+    fs::write(&path, input).map_err(|error| ClientError::Io(path.clone(), error))?;
+
+    Ok(Fetched::Downloaded(path))
+}
+
+/// Downloads the puzzle text of `day` into its `README.md`, replacing what
+/// was there so that part two shows up once it is unlocked. An empty
+/// `README.txt` placeholder left by `aoc new` is removed.
+pub fn fetch_readme(
+    workspace: &Workspace,
+    client: &Client,
+    day: u8,
+) -> Result<PathBuf, ClientError> {
+    let markdown = html::to_markdown(&client.puzzle(day)?);
+    if markdown.is_empty() {
+        return Err(ClientError::NoPuzzle(day));
+    }
+
+    let dir = workspace.day_dir(day);
+    fs::create_dir_all(&dir).map_err(|error| ClientError::Io(dir.clone(), error))?;
+    let path = dir.join("README.md");
+    fs::write(&path, markdown).map_err(|error| ClientError::Io(path.clone(), error))?;
+
+    let placeholder = dir.join("README.txt");
+    if fs::read_to_string(&placeholder).is_ok_and(|text| text.trim().is_empty()) {
+        fs::remove_file(&placeholder).map_err(|error| ClientError::Io(placeholder, error))?;
+    }
+
+    Ok(path)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::http::tests::stub_server;
+    use crate::http::PlainHttp;
+    use crate::scaffold::tests::scratch_dir;
+    use rstest::*;
+
+    pub(crate) fn stub_client(url: &str) -> Client {
+        Client::new(
+            url,
+            Some("s3cret".to_string()),
+            Box::new(PlainHttp::default()),
+        )
+    }
+
+    #[test]
+    fn fetch_downloads_once() {
+        let workspace = Workspace::new(scratch_dir("fetch"));
+        let (url, requests) = stub_server(vec![(200, "1abc2\n".to_string())]);
+        let client = stub_client(&url);
+
+        let first = fetch_input(&workspace, &client, 1).unwrap();
+        let second = fetch_input(&workspace, &client, 1).unwrap();
+
+        assert_eq!(first, Fetched::Downloaded(workspace.cached_input_path(1)));
+        assert_eq!(second, Fetched::Cached(workspace.cached_input_path(1)));
+        assert_eq!(workspace.input_path(1), workspace.cached_input_path(1));
+        assert_eq!(
+            fs::read_to_string(workspace.input_path(1)).unwrap(),
+            "1abc2\n"
+        );
+        let received = requests.recv().unwrap();
+        assert_eq!(received.path, "/2023/day/1/input");
+        assert_eq!(received.header("Cookie"), Some("session=s3cret"));
+        assert!(requests.try_recv().is_err());
+    }
+
+    #[test]
+    fn fetch_keeps_committed_input() {
+        let workspace = Workspace::new(scratch_dir("fetch-committed"));
+        fs::create_dir_all(workspace.day_dir(3)).unwrap();
+        fs::write(workspace.input_path(3), "467..114..\n").unwrap();
+
+        let fetched = fetch_input(&workspace, &stub_client("http://127.0.0.1:1"), 3).unwrap();
+
+        assert_eq!(
+            fetched,
+            Fetched::Cached(workspace.day_dir(3).join("input.txt"))
+        );
+        assert!(!workspace.cached_input_path(3).exists());
+    }
+
+    #[test]
+    fn fetch_reports_errors() {
+        let workspace = Workspace::new(scratch_dir("fetch-error"));
+        let (url, _requests) = stub_server(vec![(404, "not unlocked".to_string())]);
+
+        let error = fetch_input(&workspace, &stub_client(&url), 25).unwrap_err();
+
+        assert!(matches!(error, ClientError::Status(404, _)));
+        assert!(!workspace.cached_input_path(25).exists());
+    }
+
+    #[rstest]
+    #[case("1234", "1234")]
+    #[case("a b&c", "a%20b%26c")]
+    fn form_encoding(#[case] answer: &str, #[case] expected: &str) {
+        assert_eq!(encode(answer), expected);
+    }
+
+    #[test]
+    fn readme_replaces_placeholder() {
+        let workspace = Workspace::new(scratch_dir("readme"));
+        fs::create_dir_all(workspace.day_dir(2)).unwrap();
+        fs::write(workspace.day_dir(2).join("README.txt"), "").unwrap();
+        let page = "<main><article><h2>--- Day 2: Cube Conundrum ---</h2>\
+                    <p>You're launched high into the atmosphere!</p></article></main>";
+        let (url, requests) = stub_server(vec![(200, page.to_string())]);
+
+        let path = fetch_readme(&workspace, &stub_client(&url), 2).unwrap();
+
+        assert_eq!(path, workspace.readme_path(2));
+        assert_eq!(
+            fs::read_to_string(path).unwrap(),
+            "--- Day 2: Cube Conundrum ---\n\nYou're launched high into the atmosphere!\n"
+        );
+        assert!(!workspace.day_dir(2).join("README.txt").exists());
+        assert_eq!(requests.recv().unwrap().path, "/2023/day/2");
+    }
+
+    #[test]
+    fn configured_backend() {
+        let config: Config =
+            toml::from_str("base_url = \"http://localhost\"\nhttp = \"curl\"").unwrap();
+
+        assert_eq!(config.http, Some(Backend::Curl));
+        assert_eq!("plain".parse(), Ok(Backend::Plain));
+        assert!("tls".parse::<Backend>().is_err());
+    }
+
+    #[test]
+    fn session_is_required() {
+        let client = Client::new("http://127.0.0.1:1", None, Box::new(PlainHttp::default()));
+
+        assert!(matches!(client.input(1), Err(ClientError::MissingSession)));
+    }
+}
@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Shape of a sequence that eventually repeats: `prefix` states lead into a
+/// loop of `period` states.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Cycle {
+    pub prefix: usize,
+    pub period: usize,
+}
+
+impl Cycle {
+    /// Earliest step whose state is the same as the one after `n` steps.
+    pub fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.prefix {
+            n
+        } else {
+            self.prefix + (n - self.prefix) % self.period
+        }
+    }
+}
+
+/// Applies `step` from `start` until a state shows up twice. Only terminates
+/// for sequences that do repeat, which any simulation over finite states does.
+pub fn find_cycle<S: Hash + Eq>(start: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut seen = HashMap::new();
+    let mut state = start;
+
+    for index in 0.. {
+        if let Some(&first) = seen.get(&state) {
+            return Cycle {
+                prefix: first,
+                period: index - first,
+            };
+        }
+
+        let next = step(&state);
+        seen.insert(state, index);
+        state = next;
+    }
+
+    unreachable!("ran out of steps before finding a cycle")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case(0, |x: &u32| (x + 1) % 5, Cycle { prefix: 0, period: 5 })]
+    #[case(7, |x: &u32| if *x > 3 { x - 1 } else { (x + 1) % 4 }, Cycle { prefix: 4, period: 4 })]
+    #[case(2, |x: &u32| *x, Cycle { prefix: 0, period: 1 })]
+    fn finds_cycle(#[case] start: u32, #[case] step: fn(&u32) -> u32, #[case] expected: Cycle) {
+        let result = find_cycle(start, step);
+
+        assert_eq!(result, expected);
+    }
+
+    #[rstest]
+    #[case(3, 3)]
+    #[case(4, 4)]
+    #[case(8, 4)]
+    #[case(1_000_000_000, 4)]
+    #[case(1_000_000_001, 5)]
+    fn equivalent_step(#[case] n: usize, #[case] expected: usize) {
+        let cycle = Cycle {
+            prefix: 4,
+            period: 4,
+        };
+
+        assert_eq!(cycle.equivalent_step(n), expected);
+    }
+}
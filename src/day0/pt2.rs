@@ -15,7 +15,7 @@ fn parse(input: &str) -> IResult<&str, u32> {
     Ok((input, 0))
 }
 
-fn process(input: &str) -> u32 {
+pub fn process(input: &str) -> u32 {
     0
 }
 
@@ -32,13 +32,4 @@ mod tests {
 
         assert_eq!(result, 0);
     }
-
-    #[test]
-    fn real_input() {
-        let input = include_str!("./input.txt");
-
-        let result = process(input);
-
-        assert_eq!(result, 0);
-    }
 }
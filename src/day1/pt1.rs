@@ -2,30 +2,107 @@ use itertools::Itertools;
 use std::collections::BTreeSet;
 use std::iter;
 
-fn process(input: &str) -> u32 {
-    0
+pub const DIGITS: [(&str, u32); 10] = [
+    ("0", 0),
+    ("1", 1),
+    ("2", 2),
+    ("3", 3),
+    ("4", 4),
+    ("5", 5),
+    ("6", 6),
+    ("7", 7),
+    ("8", 8),
+    ("9", 9),
+];
+
+/// Finds every token of a table in a line, including tokens that share
+/// letters with the one before them (`twone` holds both `two` and `one`).
+#[derive(Debug, Clone)]
+pub struct Scanner<'a> {
+    tokens: Vec<(&'a str, u32)>,
+}
+
+impl<'a> Scanner<'a> {
+    pub fn new(tokens: impl IntoIterator<Item = (&'a str, u32)>) -> Self {
+        Scanner {
+            tokens: tokens.into_iter().collect(),
+        }
+    }
+
+    pub fn digits() -> Self {
+        Scanner::new(DIGITS)
+    }
+
+    pub fn with_tokens(mut self, tokens: impl IntoIterator<Item = (&'a str, u32)>) -> Self {
+        self.tokens.extend(tokens);
+        self
+    }
+
+    /// Values of the tokens in `line`, in the order they start.
+    pub fn values<'b>(&'b self, line: &'b str) -> impl Iterator<Item = u32> + 'b {
+        (0..line.len()).filter_map(move |start| {
+            let rest = line.get(start..)?;
+
+            self.tokens
+                .iter()
+                .find(|(token, _)| rest.starts_with(token))
+                .map(|(_, value)| *value)
+        })
+    }
+
+    /// First and last value of the line read as a two-digit number.
+    pub fn calibration_value(&self, line: &str) -> Option<u32> {
+        let mut values = self.values(line);
+        let first = values.next()?;
+        let last = values.last().unwrap_or(first);
+
+        Some(first * 10 + last)
+    }
+}
+
+pub fn calibration_sum(input: &str, scanner: &Scanner) -> u32 {
+    input
+        .lines()
+        .map(|line| {
+            scanner
+                .calibration_value(line)
+                .expect("every line should have a digit")
+        })
+        .sum()
+}
+
+pub fn process(input: &str) -> u32 {
+    calibration_sum(input, &Scanner::digits())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rstest::*;
 
-    #[test]
-    fn base_example() {
-        let input = r#"
-"#;
-
-        let result = process(input);
+    #[rstest]
+    #[case("1abc2", 12)]
+    #[case("pqr3stu8vwx", 38)]
+    #[case("a1b2c3d4e5f", 15)]
+    #[case("treb7uchet", 77)]
+    fn calibration_value(#[case] line: &str, #[case] expected: u32) {
+        let result = Scanner::digits().calibration_value(line);
 
-        assert_eq!(result, 0);
+        assert_eq!(result, Some(expected));
     }
 
     #[test]
-    fn real_input() {
-        let input = include_str!("./input.txt");
+    fn base_example() {
+        let input = r#"
+1abc2
+pqr3stu8vwx
+a1b2c3d4e5f
+treb7uchet
+"#
+        .trim();
 
         let result = process(input);
 
-        assert_eq!(result, 0);
+        assert_eq!(result, 142);
     }
 }
@@ -1,31 +1,66 @@
-use itertools::Itertools;
-use std::collections::BTreeSet;
-use std::iter;
+use super::pt1::{calibration_sum, Scanner};
 
-fn process(input: &str) -> u32 {
-    0
+pub const ENGLISH_DIGITS: [(&str, u32); 9] = [
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
+
+pub fn process(input: &str) -> u32 {
+    calibration_sum(input, &Scanner::digits().with_tokens(ENGLISH_DIGITS))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rstest::*;
 
-    #[test]
-    fn base_example() {
-        let input = r#"
-"#;
+    #[rstest]
+    #[case("two1nine", 29)]
+    #[case("eightwothree", 83)]
+    #[case("abcone2threexyz", 13)]
+    #[case("xtwone3four", 24)]
+    #[case("4nineeightseven2", 42)]
+    #[case("zoneight234", 14)]
+    #[case("7pqrstsixteen", 76)]
+    #[case("twone", 21)]
+    #[case("eightwo", 82)]
+    #[case("oneight", 18)]
+    fn calibration_value(#[case] line: &str, #[case] expected: u32) {
+        let scanner = Scanner::digits().with_tokens(ENGLISH_DIGITS);
 
-        let result = process(input);
+        assert_eq!(scanner.calibration_value(line), Some(expected));
+    }
+
+    #[test]
+    fn other_languages() {
+        let scanner = Scanner::digits().with_tokens([("um", 1), ("dois", 2), ("tres", 3)]);
 
-        assert_eq!(result, 0);
+        assert_eq!(scanner.calibration_value("xdoisum7tres"), Some(23));
+        assert_eq!(scanner.calibration_value("nothing here"), None);
     }
 
     #[test]
-    fn real_input() {
-        let input = include_str!("./input.txt");
+    fn base_example() {
+        let input = r#"
+two1nine
+eightwothree
+abcone2threexyz
+xtwone3four
+4nineeightseven2
+zoneight234
+7pqrstsixteen
+"#
+        .trim();
 
         let result = process(input);
 
-        assert_eq!(result, 0);
+        assert_eq!(result, 281);
     }
 }
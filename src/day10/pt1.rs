@@ -12,7 +12,7 @@ use nom::{
 };
 
 #[derive(Debug, PartialEq, Eq, Clone)]
-enum Pipe {
+pub enum Pipe {
     Vertical,
     Horizontal,
     NE,
@@ -38,7 +38,7 @@ impl From<char> for Pipe {
     }
 }
 
-fn parse(input: &str) -> Vec<Vec<Pipe>> {
+pub fn parse_input(input: &str) -> Vec<Vec<Pipe>> {
     input
         .lines()
         .map(|line| line.chars().map(Pipe::from).collect())
@@ -164,9 +164,7 @@ struct Position {
     pipe: Pipe,
 }
 
-fn process(input: &str) -> u32 {
-    let grid = parse(input);
-
+pub fn solve(grid: &[Vec<Pipe>]) -> u32 {
     let mut pipes = grid
         .iter()
         .enumerate()
@@ -232,6 +230,10 @@ fn process(input: &str) -> u32 {
     u32::try_from((pipes.len() - 1) / 2).unwrap()
 }
 
+pub fn process(input: &str) -> u32 {
+    solve(&parse_input(input))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -259,13 +261,4 @@ LJ...",
 
         assert_eq!(result, expected);
     }
-
-    #[test]
-    fn real_input() {
-        let input = include_str!("./input.txt");
-
-        let result = process(input);
-
-        assert_eq!(result, 6800);
-    }
 }
@@ -13,21 +13,86 @@ use nom::{
 
 use grid::*;
 
-#[derive(Debug, PartialEq, Eq, Default, Clone, Hash)]
-struct Position {
-    x: usize,
-    y: usize,
+#[derive(Debug, PartialEq, Eq, Default, Clone, Copy, Hash)]
+pub struct Position {
+    pub x: u64,
+    pub y: u64,
 }
 
 #[derive(Debug, PartialEq, Eq, Default, Clone, Hash)]
-enum Universe {
+pub enum Universe {
     Galaxy(Position),
 
     #[default]
     Emptiness,
 }
 
-fn parse(input: &str) -> Grid<Universe> {
+/// Galaxies of an image after every empty row and column has been widened
+/// `expansion_factor` times, in reading order. The factor must be at least 1,
+/// as there is no shrinking an empty line away.
+#[derive(Debug, Clone)]
+pub struct ExpandedUniverse {
+    galaxies: Vec<Position>,
+}
+
+impl ExpandedUniverse {
+    pub fn new(input: &str, expansion_factor: u64) -> Self {
+        Self::from_grid(&parse_input(input), expansion_factor)
+    }
+
+    pub fn from_grid(grid: &Grid<Universe>, expansion_factor: u64) -> Self {
+        assert!(
+            expansion_factor > 0,
+            "an expansion factor must be at least 1"
+        );
+        let (empty_x, empty_y) = compute_empty(grid, expansion_factor);
+
+        let galaxies = grid
+            .iter()
+            .filter_map(|x| match x {
+                Universe::Galaxy(p) => Some(Position {
+                    x: p.x + empty_x[p.x as usize],
+                    y: p.y + empty_y[p.y as usize],
+                }),
+                Universe::Emptiness => None,
+            })
+            .collect();
+
+        ExpandedUniverse { galaxies }
+    }
+
+    pub fn galaxies(&self) -> &[Position] {
+        &self.galaxies
+    }
+
+    /// Shortest path between the galaxies at indexes `a` and `b`.
+    pub fn distance(&self, a: usize, b: usize) -> Option<u64> {
+        let p1 = self.galaxies.get(a)?;
+        let p2 = self.galaxies.get(b)?;
+
+        Some(manhattan_distance(p1, p2))
+    }
+
+    /// Every unordered pair of galaxy indexes with its distance.
+    pub fn pairs(&self) -> impl Iterator<Item = (usize, usize, u64)> + '_ {
+        (0..self.galaxies.len()).tuple_combinations().map(|(a, b)| {
+            (
+                a,
+                b,
+                manhattan_distance(&self.galaxies[a], &self.galaxies[b]),
+            )
+        })
+    }
+
+    /// Sum of the distances between every pair of galaxies, computed per axis
+    /// in O(n log n) instead of visiting each pair.
+    pub fn distance_sum(&self) -> u64 {
+        axis_distance_sum(self.galaxies.iter().map(|p| p.x).collect())
+            + axis_distance_sum(self.galaxies.iter().map(|p| p.y).collect())
+    }
+}
+
+pub fn parse_input(input: &str) -> Grid<Universe> {
     let grid: Vec<Vec<Universe>> = input
         .lines()
         .enumerate()
@@ -36,7 +101,10 @@ fn parse(input: &str) -> Grid<Universe> {
                 .enumerate()
                 .map(move |(ii, c)| {
                     if c == '#' {
-                        Universe::Galaxy(Position { x: ii, y: i })
+                        Universe::Galaxy(Position {
+                            x: ii as u64,
+                            y: i as u64,
+                        })
                     } else {
                         Universe::Emptiness
                     }
@@ -51,34 +119,36 @@ fn parse(input: &str) -> Grid<Universe> {
     })
 }
 
-fn manhattan_distance(p1: &Position, p2: &Position, empty_x: &[i32], empty_y: &[i32]) -> i32 {
-    let p1_plus_x = empty_x.get(p1.x).unwrap();
-    let p1_plus_y = empty_y.get(p1.y).unwrap();
-
-    let p2_plus_x = empty_x.get(p2.x).unwrap();
-    let p2_plus_y = empty_y.get(p2.y).unwrap();
-    match (
-        i32::try_from(p1.x),
-        i32::try_from(p1.y),
-        i32::try_from(p2.x),
-        i32::try_from(p2.y),
-    ) {
-        (Ok(p1x), Ok(p1y), Ok(p2x), Ok(p2y)) => {
-            ((p1x + p1_plus_x) - (p2x + p2_plus_x)).abs()
-                + ((p1y + p1_plus_y) - (p2y + p2_plus_y)).abs()
-        }
-        _ => 0,
-    }
+fn manhattan_distance(p1: &Position, p2: &Position) -> u64 {
+    p1.x.abs_diff(p2.x) + p1.y.abs_diff(p2.y)
 }
 
-fn compute_empty(grid: &Grid<Universe>) -> (Vec<i32>, Vec<i32>) {
+/// Sorted coordinates: the i-th one is at least as far as the i before it, so it
+/// contributes `c * i - (sum of those before)` to the total.
+fn axis_distance_sum(mut coordinates: Vec<u64>) -> u64 {
+    coordinates.sort_unstable();
+
+    coordinates
+        .iter()
+        .enumerate()
+        .fold((0, 0), |(total, prefix), (i, &c)| {
+            (total + c * i as u64 - prefix, prefix + c)
+        })
+        .0
+}
+
+/// Extra offset each column and row gets once the empty ones before it (and
+/// itself) have grown to `expansion_factor` times their size.
+fn compute_empty(grid: &Grid<Universe>, expansion_factor: u64) -> (Vec<u64>, Vec<u64>) {
+    let growth = expansion_factor - 1;
+
     let mut x_empty = vec![0; grid.cols()];
     let mut y_empty = vec![0; grid.rows()];
 
     let mut x_empty_acc = 0;
     for (index, mut col) in grid.iter_cols().enumerate() {
         if col.all(|x| matches!(x, Universe::Emptiness)) {
-            x_empty_acc += 1;
+            x_empty_acc += growth;
         }
         x_empty[index] = x_empty_acc;
     }
@@ -86,7 +156,7 @@ fn compute_empty(grid: &Grid<Universe>) -> (Vec<i32>, Vec<i32>) {
     let mut y_empty_acc = 0;
     for (index, mut row) in grid.iter_rows().enumerate() {
         if row.all(|x| matches!(x, Universe::Emptiness)) {
-            y_empty_acc += 1;
+            y_empty_acc += growth;
         }
         y_empty[index] = y_empty_acc;
     }
@@ -94,42 +164,42 @@ fn compute_empty(grid: &Grid<Universe>) -> (Vec<i32>, Vec<i32>) {
     (x_empty, y_empty)
 }
 
-fn process(input: &str) -> i32 {
-    let grid = parse(input);
-
-    let galaxies: Vec<_> = grid
-        .iter_rows()
-        .flat_map(|x| {
-            x.filter(|y| matches!(y, Universe::Galaxy(p)))
-                .collect::<Vec<_>>()
-        })
-        .collect();
-
-    let x: Vec<(&Universe, &Universe)> = galaxies.iter().cloned().tuple_combinations().collect();
-
-    let (empty_x, empty_y) = compute_empty(&grid);
+pub fn galaxy_distance_sum(input: &str, expansion_factor: u64) -> u64 {
+    ExpandedUniverse::new(input, expansion_factor).distance_sum()
+}
 
-    let distances: Vec<i32> = x
-        .iter()
-        .map(|(x, y)| match (x, y) {
-            (Universe::Galaxy(p1), Universe::Galaxy(p2)) => {
-                manhattan_distance(p1, p2, &empty_x, &empty_y)
-            }
-            _ => 0,
-        })
-        .collect();
+pub fn solve(grid: &Grid<Universe>) -> u64 {
+    ExpandedUniverse::from_grid(grid, 2).distance_sum()
+}
 
-    distances.iter().sum()
+pub fn process(input: &str) -> u64 {
+    solve(&parse_input(input))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
     use rstest::*;
 
-    #[rstest]
-    #[case("
-...#......
+    /// An image of up to 12 by 12, about a fifth of it galaxies.
+    fn image() -> impl Strategy<Value = String> {
+        (1..=12_usize, 1..=12_usize)
+            .prop_flat_map(|(rows, cols)| {
+                prop::collection::vec(prop::collection::vec(prop::bool::weighted(0.2), cols), rows)
+            })
+            .prop_map(|rows| {
+                rows.iter()
+                    .map(|row| {
+                        row.iter()
+                            .map(|&galaxy| if galaxy { '#' } else { '.' })
+                            .collect::<String>()
+                    })
+                    .join("\n")
+            })
+    }
+
+    const EXAMPLE: &str = "...#......
 .......#..
 #.........
 ..........
@@ -138,21 +208,68 @@ mod tests {
 .........#
 ..........
 .......#..
-#...#.....
-".trim(), 374)]
+#...#.....";
+
+    #[rstest]
+    #[case(EXAMPLE, 374)]
     #[case("", 0)]
-    fn base_example(#[case] input: &str, #[case] expected: i32) {
+    fn base_example(#[case] input: &str, #[case] expected: u64) {
         let result = process(input);
 
         assert_eq!(result, expected);
     }
 
+    #[rstest]
+    #[case(2, 374)]
+    #[case(10, 1030)]
+    #[case(100, 8410)]
+    fn expansion_factors(#[case] expansion_factor: u64, #[case] expected: u64) {
+        let result = galaxy_distance_sum(EXAMPLE, expansion_factor);
+
+        assert_eq!(result, expected);
+    }
+
     #[test]
-    fn real_input() {
-        let input = include_str!("./input.txt");
+    #[should_panic(expected = "an expansion factor must be at least 1")]
+    fn no_expansion_factor() {
+        ExpandedUniverse::new(EXAMPLE, 0);
+    }
 
-        let result = process(input);
+    #[rstest]
+    #[case(4, 8, 9)]
+    #[case(0, 6, 15)]
+    #[case(2, 5, 17)]
+    #[case(7, 8, 5)]
+    fn single_pair(#[case] a: usize, #[case] b: usize, #[case] expected: u64) {
+        let universe = ExpandedUniverse::new(EXAMPLE, 2);
 
-        assert_eq!(result, 10173804);
+        assert_eq!(universe.distance(a, b), Some(expected));
+        assert_eq!(universe.distance(b, a), Some(expected));
+    }
+
+    #[test]
+    fn pairs_match_distance_sum() {
+        let universe = ExpandedUniverse::new(EXAMPLE, 2);
+
+        assert_eq!(universe.pairs().count(), 36);
+        assert_eq!(
+            universe.pairs().map(|(_, _, d)| d).sum::<u64>(),
+            universe.distance_sum()
+        );
+    }
+
+    proptest! {
+        /// Every pair visited one by one against the per-axis prefix sums.
+        #[test]
+        fn pairs_match_prefix_sums(image in image(), expansion_factor in 1..=1_000_000_u64) {
+            let universe = ExpandedUniverse::new(&image, expansion_factor);
+
+            let n = universe.galaxies().len();
+            prop_assert_eq!(universe.pairs().count(), n * n.saturating_sub(1) / 2);
+            prop_assert_eq!(
+                universe.pairs().map(|(_, _, d)| d).sum::<u64>(),
+                universe.distance_sum()
+            );
+        }
     }
 }
@@ -1,6 +1,6 @@
 use core::fmt;
 use itertools::Itertools;
-use std::collections::{BTreeSet, VecDeque};
+use std::collections::BTreeSet;
 use std::iter;
 
 use nom::{
@@ -14,14 +14,16 @@ use nom::{
     IResult,
 };
 
+use crate::trace;
+
 #[derive(Debug, Clone)]
-struct SpringRecord {
-    springs: VecDeque<Spring>,
-    damaged: VecDeque<i32>,
+pub struct SpringRecord {
+    springs: Vec<Spring>,
+    damaged: Vec<usize>,
 }
 
-#[derive(Debug, Default, Clone)]
-enum Spring {
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Spring {
     Damaged,
     Operational,
     #[default]
@@ -39,8 +41,41 @@ impl fmt::Display for Spring {
     }
 }
 
-fn parse_springs(input: Vec<char>) -> VecDeque<Spring> {
-    dbg!(&input);
+impl SpringRecord {
+    /// Repeats the record `factor` times, joining the springs with an unknown one.
+    pub fn unfold(&self, factor: usize) -> SpringRecord {
+        let springs = itertools::Itertools::intersperse(
+            iter::repeat_n(self.springs.clone(), factor),
+            vec![Spring::Unkown],
+        )
+        .flatten()
+        .collect();
+
+        SpringRecord {
+            springs,
+            damaged: self.damaged.repeat(factor),
+        }
+    }
+
+    pub fn arrangements(&self) -> u64 {
+        count_arrangements(&self.springs, &self.damaged)
+    }
+
+    /// Every valid concrete arrangement, in reading order with `.` before `#`.
+    pub fn solutions(&self) -> Arrangements<'_> {
+        let mut counter = Counter::new(&self.springs, &self.damaged);
+        let stack = if counter.count(0, 0, 0) > 0 {
+            vec![(0, 0, Vec::new())]
+        } else {
+            Vec::new()
+        };
+
+        Arrangements { counter, stack }
+    }
+}
+
+fn parse_springs(input: Vec<char>) -> Vec<Spring> {
+    trace!(Trace, "{input:?}");
     input
         .iter()
         .map(|c| match c {
@@ -59,11 +94,11 @@ fn parse(input: &str) -> IResult<&str, Vec<SpringRecord>> {
             separated_pair(
                 many0(none_of(" ")),
                 space1,
-                separated_list1(tag(","), complete::i32),
+                separated_list1(tag(","), complete::u32),
             ),
-            |(springs, damaged): (Vec<char>, Vec<i32>)| SpringRecord {
+            |(springs, damaged): (Vec<char>, Vec<u32>)| SpringRecord {
                 springs: parse_springs(springs),
-                damaged: VecDeque::from(damaged),
+                damaged: damaged.into_iter().map(|x| x as usize).collect(),
             },
         ),
     )(input)?;
@@ -71,117 +106,222 @@ fn parse(input: &str) -> IResult<&str, Vec<SpringRecord>> {
     Ok((input, records))
 }
 
-fn compute_arrangement(
-    mut springs: VecDeque<Spring>,
-    mut remaining_damaged: VecDeque<i32>,
-    damaged_count: i32,
-    mut so_far: Vec<Spring>,
-    r: String,
-) -> i32 {
-    let springs_l = &springs.clone();
-    let rmd = &remaining_damaged.clone();
-    let damaged_count_l = &damaged_count.clone();
-
-    let log = format!(
-        "{:?} --- {} {:?} {:?} {}",
-        &so_far.iter().map(|x| x.to_string()).join(""),
-        r,
-        springs_l.iter().map(|x| x.to_string()).join(""),
-        rmd,
-        damaged_count_l
-    );
-
-    let current_spring = springs.pop_front();
-
-    let maybe_group = remaining_damaged.front();
-
-    match (current_spring, maybe_group) {
-        (Some(Spring::Operational), Some(current_group)) => {
-            so_far.push(Spring::Operational);
-            if &damaged_count == current_group {
-                remaining_damaged.pop_front();
-                compute_arrangement(springs, remaining_damaged, 0, so_far, r)
-            } else if damaged_count > 0 {
-                0
-            } else {
-                compute_arrangement(springs, remaining_damaged, damaged_count, so_far, r)
-            }
-        }
-        (Some(Spring::Damaged), Some(current_group)) => {
-            so_far.push(Spring::Damaged);
-            if (damaged_count + 1) > *current_group {
-                0
-            } else {
-                compute_arrangement(springs, remaining_damaged, damaged_count + 1, so_far, r)
-            }
+/// Counts the ways of resolving every unknown spring so that the damaged runs
+/// match `damaged`, memoized on (spring index, group index, current run).
+pub fn count_arrangements(springs: &[Spring], damaged: &[usize]) -> u64 {
+    Counter::new(springs, damaged).count(0, 0, 0)
+}
+
+struct Counter<'a> {
+    springs: &'a [Spring],
+    damaged: &'a [usize],
+    runs: usize,
+    memo: Vec<Option<u64>>,
+}
+
+impl<'a> Counter<'a> {
+    fn new(springs: &'a [Spring], damaged: &'a [usize]) -> Self {
+        let runs = damaged.iter().max().copied().unwrap_or(0) + 1;
+
+        Counter {
+            springs,
+            damaged,
+            runs,
+            memo: vec![None; springs.len() * (damaged.len() + 1) * runs],
         }
-        (Some(Spring::Unkown), _) => {
-            let mut operational = springs.clone();
-
-            springs.push_front(Spring::Damaged);
-            operational.push_front(Spring::Operational);
-
-            compute_arrangement(
-                springs,
-                remaining_damaged.clone(),
-                damaged_count,
-                so_far.clone(),
-                r.clone(),
-            ) + compute_arrangement(
-                operational,
-                remaining_damaged.clone(),
-                damaged_count,
-                so_far.clone(),
-                r.clone(),
-            )
+    }
+
+    fn count(&mut self, spring: usize, group: usize, run: usize) -> u64 {
+        let Some(&current_spring) = self.springs.get(spring) else {
+            return match self.damaged.get(group) {
+                None => u64::from(run == 0),
+                Some(&size) => u64::from(group + 1 == self.damaged.len() && run == size),
+            };
+        };
+
+        let index = (spring * (self.damaged.len() + 1) + group) * self.runs + run;
+        if let Some(count) = self.memo[index] {
+            return count;
         }
-        (None, Some(current_group)) if remaining_damaged.len() == 1 => {
-            if *current_group == damaged_count {
-                println!("a: {}", &log);
-                1
-            } else {
-                0
+
+        let count = transitions(current_spring, self.damaged, group, run)
+            .map(|(_, group, run)| self.count(spring + 1, group, run))
+            .sum();
+
+        self.memo[index] = Some(count);
+        count
+    }
+}
+
+/// The concrete springs `current` can be at this point, with the group and run
+/// that follow from picking each of them.
+fn transitions(
+    current: Spring,
+    damaged: &[usize],
+    group: usize,
+    run: usize,
+) -> impl Iterator<Item = (Spring, usize, usize)> {
+    let as_damaged = matches!(current, Spring::Damaged | Spring::Unkown)
+        && matches!(damaged.get(group), Some(&size) if run < size);
+    let as_operational = matches!(current, Spring::Operational | Spring::Unkown)
+        && (run == 0 || damaged.get(group) == Some(&run));
+
+    let operational_group = if run == 0 { group } else { group + 1 };
+
+    [
+        as_operational.then_some((Spring::Operational, operational_group, 0)),
+        as_damaged.then_some((Spring::Damaged, group, run + 1)),
+    ]
+    .into_iter()
+    .flatten()
+}
+
+/// Lazily walks every valid concrete arrangement of a record, skipping any
+/// branch the counter knows has no solutions so each step stays cheap.
+pub struct Arrangements<'a> {
+    counter: Counter<'a>,
+    stack: Vec<(usize, usize, Vec<Spring>)>,
+}
+
+impl Iterator for Arrangements<'_> {
+    type Item = Vec<Spring>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((group, run, so_far)) = self.stack.pop() {
+            let spring = so_far.len();
+            let Some(&current_spring) = self.counter.springs.get(spring) else {
+                return Some(so_far);
+            };
+
+            let next: Vec<_> = transitions(current_spring, self.counter.damaged, group, run)
+                .filter(|&(_, group, run)| self.counter.count(spring + 1, group, run) > 0)
+                .collect();
+
+            for (chosen, group, run) in next.into_iter().rev() {
+                let mut so_far = so_far.clone();
+                so_far.push(chosen);
+                self.stack.push((group, run, so_far));
             }
         }
-        (Some(Spring::Damaged), None) => 0,
-        (Some(Spring::Operational), None) => {
-            compute_arrangement(springs, remaining_damaged, damaged_count, so_far, r)
-        }
-        (None, None) => {
-            println!("c: {}", &log);
-            1
-        }
-        (_, _) => 0,
+
+        None
     }
 }
 
-fn arrangement(springs: VecDeque<Spring>, remaining_damaged: VecDeque<i32>) -> i32 {
-    let r = remaining_damaged
-        .clone()
+pub fn render(springs: &[Spring]) -> String {
+    springs.iter().map(|x| x.to_string()).join("")
+}
+
+/// The first `limit` concrete arrangements of every record, rendered as text.
+pub fn list_arrangements(input: &str, limit: usize) -> Vec<Vec<String>> {
+    parse_input(input)
         .iter()
-        .map(|x| x.to_string())
-        .join(", ");
-    compute_arrangement(springs, remaining_damaged, 0, Vec::new(), r)
+        .map(|record| record.solutions().take(limit).map(|x| render(&x)).collect())
+        .collect()
 }
 
-fn process(input: &str) -> i32 {
-    let (input, records) = parse(input).expect("xuxu");
+pub fn parse_input(input: &str) -> Vec<SpringRecord> {
+    let (_, records) = parse(input).expect("xuxu");
+
+    records
+}
 
+/// Sum of the arrangements of every record once unfolded `unfold` times.
+pub(super) fn sum_arrangements(records: &[SpringRecord], unfold: usize) -> u64 {
     records
-        .into_iter()
-        .map(|record| arrangement(record.springs, record.damaged))
+        .iter()
+        .map(|record| record.unfold(unfold).arrangements())
         .sum()
 }
 
+pub fn solve(records: &[SpringRecord]) -> u64 {
+    sum_arrangements(records, 1)
+}
+
+pub fn process(input: &str) -> u64 {
+    solve(&parse_input(input))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
     use rstest::*;
 
+    fn runs(springs: &[Spring]) -> Vec<usize> {
+        springs
+            .split(|spring| *spring != Spring::Damaged)
+            .map(<[Spring]>::len)
+            .filter(|len| *len > 0)
+            .collect()
+    }
+
+    /// Tries every way of resolving the unknown springs.
+    fn brute_force(record: &SpringRecord) -> u64 {
+        let unknown: Vec<usize> = (0..record.springs.len())
+            .filter(|&i| record.springs[i] == Spring::Unkown)
+            .collect();
+
+        (0..1_u32 << unknown.len())
+            .filter(|bits| {
+                let mut springs = record.springs.clone();
+                for (bit, &i) in unknown.iter().enumerate() {
+                    springs[i] = if bits & (1 << bit) != 0 {
+                        Spring::Damaged
+                    } else {
+                        Spring::Operational
+                    };
+                }
+                runs(&springs) == record.damaged
+            })
+            .count() as u64
+    }
+
+    /// A record line: some concrete springs with a few hidden behind `?`,
+    /// listed either with their own runs, so there is at least one
+    /// arrangement, or with arbitrary ones.
+    fn record_line() -> impl Strategy<Value = String> {
+        let springs = prop::collection::vec((any::<bool>(), prop::bool::weighted(0.4)), 1..=14);
+        let arbitrary = prop::option::of(prop::collection::vec(1..=4_usize, 1..=4));
+
+        (springs, arbitrary).prop_filter_map(
+            "a record lists at least one run",
+            |(springs, arbitrary)| {
+                let concrete: Vec<Spring> = springs
+                    .iter()
+                    .map(|&(damaged, _)| {
+                        if damaged {
+                            Spring::Damaged
+                        } else {
+                            Spring::Operational
+                        }
+                    })
+                    .collect();
+                let damaged = arbitrary.unwrap_or_else(|| runs(&concrete));
+                if damaged.is_empty() {
+                    return None;
+                }
+
+                let shown: String = springs
+                    .iter()
+                    .zip(&concrete)
+                    .map(|(&(_, hidden), spring)| {
+                        if hidden {
+                            "?".to_string()
+                        } else {
+                            spring.to_string()
+                        }
+                    })
+                    .collect();
+                Some(format!("{shown} {}", damaged.iter().join(",")))
+            },
+        )
+    }
+
     #[test]
     fn record1() {
         use Spring::*;
-        let springs = VecDeque::from(vec![
+        let springs = vec![
             Unkown,
             Unkown,
             Unkown,
@@ -189,57 +329,57 @@ mod tests {
             Damaged,
             Damaged,
             Damaged,
-        ]);
-        let damaged = VecDeque::from(vec![1, 1, 3]);
+        ];
+        let damaged = vec![1, 1, 3];
 
-        let result = arrangement(springs, damaged);
+        let result = count_arrangements(&springs, &damaged);
         assert_eq!(result, 1);
     }
 
     #[test]
     fn my_test() {
         use Spring::*;
-        let springs = VecDeque::from(vec![Unkown, Unkown, Damaged]);
-        let damaged = VecDeque::from(vec![1, 1]);
+        let springs = vec![Unkown, Unkown, Damaged];
+        let damaged = vec![1, 1];
 
-        let result = arrangement(springs, damaged);
+        let result = count_arrangements(&springs, &damaged);
         assert_eq!(result, 1);
     }
 
     #[test]
     fn my_test2() {
         use Spring::*;
-        let springs = VecDeque::from(vec![Unkown, Unkown, Damaged, Operational]);
-        let damaged = VecDeque::from(vec![1, 1]);
+        let springs = vec![Unkown, Unkown, Damaged, Operational];
+        let damaged = vec![1, 1];
 
-        let result = arrangement(springs, damaged);
+        let result = count_arrangements(&springs, &damaged);
         assert_eq!(result, 1);
     }
 
     #[test]
     fn my_test3() {
         use Spring::*;
-        let springs = VecDeque::from(vec![Unkown, Unkown, Damaged, Unkown, Operational]);
-        let damaged = VecDeque::from(vec![1, 1]);
+        let springs = vec![Unkown, Unkown, Damaged, Unkown, Operational];
+        let damaged = vec![1, 1];
 
-        let result = arrangement(springs, damaged);
+        let result = count_arrangements(&springs, &damaged);
         assert_eq!(result, 1);
     }
 
     #[test]
     fn my_test4() {
         use Spring::*;
-        let springs = VecDeque::from(vec![Unkown, Unkown, Damaged, Unkown, Damaged]);
-        let damaged = VecDeque::from(vec![1, 1]);
+        let springs = vec![Unkown, Unkown, Damaged, Unkown, Damaged];
+        let damaged = vec![1, 1];
 
-        let result = arrangement(springs, damaged);
+        let result = count_arrangements(&springs, &damaged);
         assert_eq!(result, 1);
     }
 
     #[test]
     fn my_test5() {
         use Spring::*;
-        let springs = VecDeque::from(vec![
+        let springs = vec![
             Unkown,
             Unkown,
             Damaged,
@@ -247,17 +387,17 @@ mod tests {
             Unkown,
             Operational,
             Damaged,
-        ]);
-        let damaged = VecDeque::from(vec![1, 1]);
+        ];
+        let damaged = vec![1, 1];
 
-        let result = arrangement(springs, damaged);
+        let result = count_arrangements(&springs, &damaged);
         assert_eq!(result, 1);
     }
 
     #[test]
     fn record2() {
         use Spring::*;
-        let springs = VecDeque::from(vec![
+        let springs = vec![
             Operational,
             Unkown,
             Unkown,
@@ -272,17 +412,17 @@ mod tests {
             Damaged,
             Damaged,
             Operational,
-        ]);
-        let damaged = VecDeque::from(vec![1, 1, 3]);
+        ];
+        let damaged = vec![1, 1, 3];
 
-        let result = arrangement(springs, damaged);
+        let result = count_arrangements(&springs, &damaged);
         assert_eq!(result, 4);
     }
 
     #[test]
     fn record5() {
         use Spring::*;
-        let springs = VecDeque::from(vec![
+        let springs = vec![
             Unkown,
             Unkown,
             Unkown,
@@ -302,23 +442,23 @@ mod tests {
             Damaged,
             Damaged,
             Operational,
-        ]);
-        let damaged = VecDeque::from(vec![1, 6, 5]);
+        ];
+        let damaged = vec![1, 6, 5];
 
-        let result = arrangement(springs, damaged);
+        let result = count_arrangements(&springs, &damaged);
         assert_eq!(result, 4);
     }
 
     #[test]
     fn record6() {
         use Spring::*;
-        let springs = VecDeque::from(vec![
+        let springs = vec![
             Unkown, Damaged, Damaged, Damaged, Unkown, Unkown, Unkown, Unkown, Unkown, Unkown,
             Unkown, Unkown,
-        ]);
-        let damaged = VecDeque::from(vec![3, 2, 1]);
+        ];
+        let damaged = vec![3, 2, 1];
 
-        let result = arrangement(springs, damaged);
+        let result = count_arrangements(&springs, &damaged);
         assert_eq!(result, 10);
     }
 
@@ -331,18 +471,95 @@ mod tests {
 ????.######..#####. 1,6,5
 ?###???????? 3,2,1
 ".trim(), 21)]
-    fn base_example(#[case] input: &str, #[case] expected: i32) {
+    fn base_example(#[case] input: &str, #[case] expected: u64) {
         let result = process(input);
 
         assert_eq!(result, expected);
     }
 
     #[test]
-    fn real_input() {
-        let input = include_str!("./input.txt");
+    fn unfold_record() {
+        let (_, records) = parse(".# 1").unwrap();
 
-        let result = process(input);
+        let unfolded = records[0].unfold(5);
+
+        assert_eq!(
+            unfolded.springs.iter().map(|x| x.to_string()).join(""),
+            ".#?.#?.#?.#?.#"
+        );
+        assert_eq!(unfolded.damaged, vec![1, 1, 1, 1, 1]);
+    }
+
+    #[test]
+    fn solutions_of_record() {
+        let (_, records) = parse("?###???????? 3,2,1").unwrap();
+
+        let solutions: Vec<String> = records[0].solutions().map(|x| render(&x)).collect();
+
+        assert_eq!(solutions.len(), 10);
+        assert_eq!(solutions.iter().unique().count(), 10);
+        assert_eq!(solutions[0], ".###....##.#");
+        assert_eq!(solutions[9], ".###.##.#...");
+    }
+
+    #[rstest]
+    #[case("???.### 1,1,3", 5, vec!["#.#.###"])]
+    #[case(".??..??...?##. 1,1,3", 2, vec!["..#...#...###.", "..#..#....###."])]
+    #[case("#.# 2", 5, vec![])]
+    fn limited_arrangements(
+        #[case] input: &str,
+        #[case] limit: usize,
+        #[case] expected: Vec<&str>,
+    ) {
+        let result = list_arrangements(input, limit);
+
+        assert_eq!(result, vec![expected]);
+    }
+
+    #[test]
+    fn solutions_are_lazy() {
+        let (_, records) = parse("?###???????? 3,2,1").unwrap();
+        let unfolded = records[0].unfold(5);
+
+        let first: Vec<_> = unfolded.solutions().take(3).collect();
+
+        assert_eq!(unfolded.arrangements(), 506250);
+        assert_eq!(first.len(), 3);
+        assert!(first.iter().all(|x| x.len() == unfolded.springs.len()));
+    }
+
+    proptest! {
+        #[test]
+        fn dp_matches_brute_force(line in record_line()) {
+            let records = parse_input(&line);
 
-        assert_eq!(result, 7922);
+            prop_assert_eq!(records[0].arrangements(), brute_force(&records[0]));
+        }
+
+        #[test]
+        fn solutions_are_the_arrangements(line in record_line()) {
+            let records = parse_input(&line);
+            let record = &records[0];
+
+            let solutions: Vec<Vec<Spring>> = record.solutions().collect();
+
+            prop_assert_eq!(solutions.len() as u64, record.arrangements());
+            for solution in &solutions {
+                prop_assert_eq!(&runs(solution), &record.damaged);
+                prop_assert!(solution
+                    .iter()
+                    .zip(&record.springs)
+                    .all(|(spring, known)| *known == Spring::Unkown || spring == known));
+            }
+        }
+
+        #[test]
+        fn document_matches_brute_force(lines in prop::collection::vec(record_line(), 1..=5)) {
+            let document = lines.join("\n");
+
+            let expected: u64 = parse_input(&document).iter().map(brute_force).sum();
+
+            prop_assert_eq!(process(&document), expected);
+        }
     }
 }
@@ -1,44 +1,38 @@
-use itertools::Itertools;
-use std::collections::BTreeSet;
-use std::iter;
+pub use super::pt1::parse_input;
 
-use nom::{
-    bytes::complete::{tag, take_until},
-    character::complete::{self, alpha1, alphanumeric1, digit1, line_ending, newline, space1},
-    combinator::rest,
-    multi::separated_list1,
-    sequence::{pair, preceded, separated_pair, terminated},
-    IResult,
-};
+use super::pt1::{sum_arrangements, SpringRecord};
 
-fn parse(input: &str) -> IResult<&str, u32> {
-    Ok((input, 0))
+pub fn solve(records: &[SpringRecord]) -> u64 {
+    sum_arrangements(records, 5)
 }
 
-fn process(input: &str) -> u32 {
-    0
+pub fn process(input: &str) -> u64 {
+    solve(&parse_input(input))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    #[test]
-    fn base_example() {
-        let input = r#"
-"#;
-
-        let result = process(input);
-
-        assert_eq!(result, 0);
-    }
-
-    #[test]
-    fn real_input() {
-        let input = include_str!("./input.txt");
-
+    use rstest::*;
+
+    #[rstest]
+    #[case("???.### 1,1,3", 1)]
+    #[case(".??..??...?##. 1,1,3", 16384)]
+    #[case("?#?#?#?#?#?#?#? 1,3,1,6", 1)]
+    #[case("????.#...#... 4,1,1", 16)]
+    #[case("????.######..#####. 1,6,5", 2500)]
+    #[case("?###???????? 3,2,1", 506250)]
+    #[case("
+???.### 1,1,3
+.??..??...?##. 1,1,3
+?#?#?#?#?#?#?#? 1,3,1,6
+????.#...#... 4,1,1
+????.######..#####. 1,6,5
+?###???????? 3,2,1
+".trim(), 525152)]
+    fn base_example(#[case] input: &str, #[case] expected: u64) {
         let result = process(input);
 
-        assert_eq!(result, 0);
+        assert_eq!(result, expected);
     }
 }
@@ -1,3 +1,4 @@
+use core::fmt;
 use grid::{grid, Grid};
 use itertools::Itertools;
 use std::collections::BTreeSet;
@@ -12,8 +13,8 @@ use nom::{
     IResult,
 };
 
-#[derive(Debug, Default, PartialEq, Eq)]
-enum Terrain {
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum Terrain {
     Ash,
     Rock,
 
@@ -22,99 +23,171 @@ enum Terrain {
 }
 
 #[derive(Debug, PartialEq, Eq)]
-enum Reflection {
+pub enum Reflection {
     Row(u32),
     Column(u32),
 }
 
-fn find_row_reflection(grid: &Grid<Terrain>) -> Reflection {
-    let result = grid
-        .iter_rows()
-        .enumerate()
-        .tuple_windows()
-        .filter(|pair| {
-            let ((index_a, a), (index_b, b)) = pair;
+impl Reflection {
+    pub fn score(&self) -> u32 {
+        match self {
+            Reflection::Column(x) => *x,
+            Reflection::Row(x) => x * 100,
+        }
+    }
+}
 
-            a.clone().collect::<Vec<_>>() == b.clone().collect::<Vec<_>>()
-        })
-        .find(|((a, _), (b, _))| {
-            let mut index_a = *a as i32;
-            let mut index_b = *b;
-
-            let mut equal = true;
-            loop {
-                index_a -= 1;
-                index_b += 1;
-                if index_a < 0 || index_b >= grid.rows() {
-                    break;
-                }
-
-                let row_a = grid.iter_row(index_a as usize);
-                let row_b = grid.iter_row(index_b);
-
-                if !row_a.eq(row_b) {
-                    equal = false;
-                    break;
-                }
-            }
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Cell {
+    pub row: usize,
+    pub column: usize,
+}
 
-            equal
-        });
+impl Cell {
+    fn transposed(self) -> Cell {
+        Cell {
+            row: self.column,
+            column: self.row,
+        }
+    }
+}
 
-    match result {
-        Some((_, (a, b))) => Reflection::Row(u32::try_from(a).unwrap()),
-        _ => Reflection::Row(0),
+impl Reflection {
+    fn transposed(self) -> Reflection {
+        match self {
+            Reflection::Row(x) => Reflection::Column(x),
+            Reflection::Column(x) => Reflection::Row(x),
+        }
     }
 }
 
-fn find_column_reflection(grid: &Grid<Terrain>) -> Reflection {
-    let result = grid
-        .iter_cols()
-        .enumerate()
-        .tuple_windows()
-        .filter(|pair| {
-            let ((index_a, a), (index_b, b)) = pair;
+/// A line of reflection and, when it only holds after cleaning one cell, the
+/// pair of cells that disagree across it. Flipping either of them fixes it.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Mirror {
+    pub reflection: Reflection,
+    pub smudge: Option<(Cell, Cell)>,
+}
 
-            a.clone().collect::<Vec<_>>() == b.clone().collect::<Vec<_>>()
-        })
-        .find(|((a, _), (b, _))| {
-            let mut index_a = *a as i32;
-            let mut index_b = *b;
-
-            let mut equal = true;
-            loop {
-                index_a -= 1;
-                index_b += 1;
-                if index_a < 0 || index_b >= grid.cols() {
-                    break;
-                }
-
-                let row_a = grid.iter_col(index_a as usize);
-                let row_b = grid.iter_col(index_b);
-
-                if !row_a.eq(row_b) {
-                    equal = false;
-                    break;
-                }
-            }
+impl Mirror {
+    fn transposed(self) -> Mirror {
+        Mirror {
+            reflection: self.reflection.transposed(),
+            smudge: self.smudge.map(|(a, b)| (a.transposed(), b.transposed())),
+        }
+    }
+}
 
-            equal
-        });
+#[derive(Debug, PartialEq, Eq)]
+pub enum MirrorError {
+    /// No line has exactly the requested number of differences across it.
+    NoReflection { pattern: usize },
+    /// More than one line qualifies, on either axis, so there is no single
+    /// score.
+    Ambiguous { pattern: usize },
+}
 
-    match result {
-        Some((_, (a, b))) => Reflection::Column(u32::try_from(a).unwrap()),
-        _ => Reflection::Column(0),
+impl fmt::Display for MirrorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MirrorError::NoReflection { pattern } => {
+                write!(f, "pattern {pattern} has no line of reflection")
+            }
+            MirrorError::Ambiguous { pattern } => {
+                write!(
+                    f,
+                    "pattern {pattern} is ambiguous, it has more than one line of reflection"
+                )
+            }
+        }
     }
 }
 
-fn find_reflection(grid: &Grid<Terrain>) -> Vec<Reflection> {
-    let column = find_column_reflection(grid);
-    let row = find_row_reflection(grid);
+impl std::error::Error for MirrorError {}
+
+/// Mismatched cells between the rows above `above` and their mirror images,
+/// giving up once there are more than `limit` of them.
+fn row_mismatches(grid: &Grid<Terrain>, above: usize, limit: usize) -> Vec<(Cell, Cell)> {
+    (0..above)
+        .rev()
+        .zip(above..grid.rows())
+        .flat_map(|(a, b)| {
+            (0..grid.cols())
+                .filter(move |&column| grid[(a, column)] != grid[(b, column)])
+                .map(move |column| (Cell { row: a, column }, Cell { row: b, column }))
+        })
+        .take(limit + 1)
+        .collect()
+}
+
+/// Every horizontal line with exactly `differences` mismatched cells across
+/// it.
+pub fn find_row_reflections(grid: &Grid<Terrain>, differences: usize) -> Vec<Mirror> {
+    (1..grid.rows())
+        .filter_map(|above| {
+            let mismatches = row_mismatches(grid, above, differences);
+
+            (mismatches.len() == differences).then(|| Mirror {
+                reflection: Reflection::Row(u32::try_from(above).unwrap()),
+                smudge: mismatches.first().copied(),
+            })
+        })
+        .collect()
+}
+
+/// Columns of `grid` are the rows of its transpose, so this reuses the row
+/// search and maps the answers back.
+pub fn find_column_reflections(grid: &Grid<Terrain>, differences: usize) -> Vec<Mirror> {
+    let mut transposed = grid.clone();
+    transposed.transpose();
+
+    find_row_reflections(&transposed, differences)
+        .into_iter()
+        .map(Mirror::transposed)
+        .collect()
+}
+
+/// The single line of reflection of the `pattern`-th grid.
+pub fn find_reflection(
+    grid: &Grid<Terrain>,
+    differences: usize,
+    pattern: usize,
+) -> Result<Mirror, MirrorError> {
+    let mut mirrors = find_column_reflections(grid, differences)
+        .into_iter()
+        .chain(find_row_reflections(grid, differences));
+
+    match (mirrors.next(), mirrors.next()) {
+        (Some(mirror), None) => Ok(mirror),
+        (Some(_), Some(_)) => Err(MirrorError::Ambiguous { pattern }),
+        (None, _) => Err(MirrorError::NoReflection { pattern }),
+    }
+}
 
-    vec![column, row]
+/// Draws the pattern with both cells of the smudge, if any, marked as `*`.
+pub fn render(grid: &Grid<Terrain>, smudge: Option<(Cell, Cell)>) -> String {
+    let is_smudge = |row, column| {
+        smudge.is_some_and(|(a, b)| {
+            let cell = Cell { row, column };
+            a == cell || b == cell
+        })
+    };
+
+    (0..grid.rows())
+        .map(|row| {
+            (0..grid.cols())
+                .map(|column| match &grid[(row, column)] {
+                    _ if is_smudge(row, column) => '*',
+                    Terrain::Rock => '#',
+                    Terrain::Ash => '.',
+                    Terrain::Empty => ' ',
+                })
+                .collect::<String>()
+        })
+        .join("\n")
 }
 
-fn parse(input: &str) -> Vec<Grid<Terrain>> {
+pub fn parse_input(input: &str) -> Vec<Grid<Terrain>> {
     input
         .split("\n\n")
         .map(|lines| {
@@ -137,19 +210,26 @@ fn parse(input: &str) -> Vec<Grid<Terrain>> {
         .collect()
 }
 
-fn process(input: &str) -> u32 {
-    let grids = parse(input);
-
+pub(super) fn summarize_patterns(
+    grids: &[Grid<Terrain>],
+    differences: usize,
+) -> Result<u32, MirrorError> {
     grids
         .iter()
-        .flat_map(find_reflection)
-        .map(|r| match r {
-            Reflection::Column(x) => x,
-            Reflection::Row(x) => x * 100,
-        })
+        .enumerate()
+        .map(|(pattern, grid)| find_reflection(grid, differences, pattern))
+        .map(|mirror| mirror.map(|mirror| mirror.reflection.score()))
         .sum()
 }
 
+pub fn solve(grids: &[Grid<Terrain>]) -> u32 {
+    summarize_patterns(grids, 0).expect("every pattern has a single mirror")
+}
+
+pub fn process(input: &str) -> u32 {
+    solve(&parse_input(input))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -162,7 +242,50 @@ mod tests {
 
         let input = grid![[Ash, Ash, Rock, Rock] [Ash, Ash, Rock, Rock] [Rock, Ash, Rock, Ash]];
 
-        assert_eq!(find_reflection(&input), vec![Column(0), Row(1)]);
+        assert_eq!(find_column_reflections(&input, 0), vec![]);
+        assert_eq!(
+            find_reflection(&input, 0, 0).map(|mirror| mirror.reflection),
+            Ok(Row(1))
+        );
+    }
+
+    #[test]
+    fn reflection_before_first_column() {
+        use Reflection::*;
+        use Terrain::*;
+
+        let input = grid![[Rock, Rock, Ash] [Ash, Ash, Rock] [Rock, Rock, Rock]];
+
+        assert_eq!(
+            find_reflection(&input, 0, 0).map(|mirror| mirror.reflection),
+            Ok(Column(1))
+        );
+    }
+
+    #[test]
+    fn column_smudge_is_mapped_back() {
+        use Terrain::*;
+
+        let input = grid![[Rock, Ash, Ash] [Ash, Ash, Rock]];
+
+        let mirrors = find_column_reflections(&input, 1);
+
+        assert_eq!(mirrors[0].reflection, Reflection::Column(1));
+        assert_eq!(
+            mirrors[0].smudge,
+            Some((Cell { row: 0, column: 0 }, Cell { row: 0, column: 1 }))
+        );
+    }
+
+    #[rstest]
+    #[case("#.\n.#", MirrorError::NoReflection { pattern: 1 })]
+    #[case("##\n##", MirrorError::Ambiguous { pattern: 1 })]
+    #[case("#.\n#.\n#.", MirrorError::Ambiguous { pattern: 1 })]
+    #[case("###\n...", MirrorError::Ambiguous { pattern: 1 })]
+    fn pattern_without_single_mirror(#[case] pattern: &str, #[case] expected: MirrorError) {
+        let input = format!("#.#\n#.#\n\n{pattern}");
+
+        assert_eq!(summarize_patterns(&parse_input(&input), 0), Err(expected));
     }
 
     #[rstest]
@@ -204,13 +327,4 @@ mod tests {
 
         assert_eq!(result, expected);
     }
-
-    #[test]
-    fn real_input() {
-        let input = include_str!("./input.txt");
-
-        let result = process(input);
-
-        assert_eq!(result, 0);
-    }
 }
@@ -1,44 +1,74 @@
-use itertools::Itertools;
-use std::collections::BTreeSet;
-use std::iter;
-
-use nom::{
-    bytes::complete::{tag, take_until},
-    character::complete::{self, alpha1, alphanumeric1, digit1, line_ending, newline, space1},
-    combinator::rest,
-    multi::separated_list1,
-    sequence::{pair, preceded, separated_pair, terminated},
-    IResult,
-};
-
-fn parse(input: &str) -> IResult<&str, u32> {
-    Ok((input, 0))
+use grid::Grid;
+
+pub use super::pt1::parse_input;
+
+use super::pt1::{find_reflection, render, summarize_patterns, Cell, Reflection, Terrain};
+
+pub fn solve(grids: &[Grid<Terrain>]) -> u32 {
+    summarize_patterns(grids, 1).expect("every pattern has a single smudged mirror")
 }
 
-fn process(input: &str) -> u32 {
-    0
+pub fn process(input: &str) -> u32 {
+    solve(&parse_input(input))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rstest::*;
 
-    #[test]
-    fn base_example() {
-        let input = r#"
-"#;
+    const FIRST: &str = "#.##..##.
+..#.##.#.
+##......#
+##......#
+..#.##.#.
+..##..##.
+#.#.##.#.";
 
-        let result = process(input);
+    const SECOND: &str = "#...##..#
+#....#..#
+..##..###
+#####.##.
+#####.##.
+..##..###
+#....#..#";
 
-        assert_eq!(result, 0);
+    #[rstest]
+    #[case(FIRST, Reflection::Row(3), (Cell { row: 0, column: 0 }, Cell { row: 5, column: 0 }))]
+    #[case(SECOND, Reflection::Row(1), (Cell { row: 0, column: 4 }, Cell { row: 1, column: 4 }))]
+    fn finds_smudge(
+        #[case] input: &str,
+        #[case] expected: Reflection,
+        #[case] smudge: (Cell, Cell),
+    ) {
+        let grids = parse_input(input);
+
+        let mirror = find_reflection(&grids[0], 1, 0).unwrap();
+
+        assert_eq!(mirror.reflection, expected);
+        assert_eq!(mirror.smudge, Some(smudge));
     }
 
     #[test]
-    fn real_input() {
-        let input = include_str!("./input.txt");
+    fn renders_smudge() {
+        let grids = parse_input(SECOND);
+        let smudge = (Cell { row: 0, column: 4 }, Cell { row: 1, column: 4 });
+
+        let result = render(&grids[0], Some(smudge));
+
+        assert_eq!(
+            result.lines().take(3).collect::<Vec<_>>(),
+            vec!["#...*#..#", "#...*#..#", "..##..###"]
+        );
+    }
 
+    #[rstest]
+    #[case(FIRST, 300)]
+    #[case(SECOND, 100)]
+    #[case(&format!("{FIRST}\n\n{SECOND}"), 400)]
+    fn base_example(#[case] input: &str, #[case] expected: u32) {
         let result = process(input);
 
-        assert_eq!(result, 0);
+        assert_eq!(result, expected);
     }
 }
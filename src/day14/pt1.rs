@@ -1,6 +1,8 @@
+use core::fmt;
 use grid::{grid, Grid};
 use itertools::Itertools;
 use std::collections::BTreeSet;
+use std::hash::{Hash, Hasher};
 use std::iter;
 
 use nom::{
@@ -12,8 +14,8 @@ use nom::{
     IResult,
 };
 
-#[derive(Debug, Default, PartialEq, Eq)]
-enum Terrain {
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Terrain {
     Round,
     Cube,
 
@@ -21,6 +23,106 @@ enum Terrain {
     Empty,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Platform {
+    grid: Grid<Terrain>,
+}
+
+impl Hash for Platform {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.grid.size().hash(state);
+        self.grid.iter().for_each(|x| x.hash(state));
+    }
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut rows = self.grid.iter_rows().map(|row| {
+            row.map(|terrain| match terrain {
+                Terrain::Round => 'O',
+                Terrain::Cube => '#',
+                Terrain::Empty => '.',
+            })
+            .collect::<String>()
+        });
+
+        write!(f, "{}", rows.join("\n"))
+    }
+}
+
+impl Platform {
+    pub fn new(input: &str) -> Self {
+        Platform { grid: parse(input) }
+    }
+
+    pub fn grid(&self) -> &Grid<Terrain> {
+        &self.grid
+    }
+
+    /// Rolls every round rock as far as it goes towards `direction`. Each line
+    /// is walked starting from the edge the rocks roll to, and each rock lands
+    /// right after the last cube rock or already settled round rock.
+    pub fn tilt(&mut self, direction: Direction) {
+        let (lines, length) = match direction {
+            Direction::North | Direction::South => (self.grid.cols(), self.grid.rows()),
+            Direction::East | Direction::West => (self.grid.rows(), self.grid.cols()),
+        };
+
+        for line in 0..lines {
+            let cell = |i: usize| match direction {
+                Direction::North => (i, line),
+                Direction::South => (length - 1 - i, line),
+                Direction::West => (line, i),
+                Direction::East => (line, length - 1 - i),
+            };
+
+            let mut free = 0;
+            for i in 0..length {
+                match self.grid[cell(i)] {
+                    Terrain::Cube => free = i + 1,
+                    Terrain::Round => {
+                        self.grid[cell(i)] = Terrain::Empty;
+                        self.grid[cell(free)] = Terrain::Round;
+                        free += 1;
+                    }
+                    Terrain::Empty => {}
+                }
+            }
+        }
+    }
+
+    /// Tilts north, west, south and then east.
+    pub fn spin_cycle(&mut self) {
+        for direction in [
+            Direction::North,
+            Direction::West,
+            Direction::South,
+            Direction::East,
+        ] {
+            self.tilt(direction);
+        }
+    }
+
+    /// Each round rock weighs the number of rows from it to the south edge.
+    pub fn north_load(&self) -> usize {
+        self.grid
+            .iter_rows()
+            .enumerate()
+            .map(|(row, cells)| {
+                (self.grid.rows() - row) * cells.filter(|x| **x == Terrain::Round).count()
+            })
+            .sum()
+    }
+}
+
 fn parse(input: &str) -> Grid<Terrain> {
     input
         .lines()
@@ -40,10 +142,20 @@ fn parse(input: &str) -> Grid<Terrain> {
         })
 }
 
-fn process(input: &str) -> u32 {
-    let grids = parse(input);
+pub fn parse_input(input: &str) -> Platform {
+    Platform::new(input)
+}
 
-    0
+pub fn solve(platform: &Platform) -> usize {
+    let mut platform = platform.clone();
+
+    platform.tilt(Direction::North);
+
+    platform.north_load()
+}
+
+pub fn process(input: &str) -> usize {
+    solve(&parse_input(input))
 }
 
 #[cfg(test)]
@@ -51,6 +163,78 @@ mod tests {
     use super::*;
     use rstest::*;
 
+    const EXAMPLE: &str = "O....#....
+O.OO#....#
+.....##...
+OO.#O....O
+.O.....O#.
+O.#..O.#.#
+..O..#O..O
+.......O..
+#....###..
+#OO..#....";
+
+    #[rstest]
+    #[case(
+        Direction::North,
+        "OOOO.#.O..
+OO..#....#
+OO..O##..O
+O..#.OO...
+........#.
+..#....#.#
+..O..#.O.O
+..O.......
+#....###..
+#....#...."
+    )]
+    #[case(
+        Direction::South,
+        ".....#....
+....#....#
+...O.##...
+...#......
+O.O....O#O
+O.#..O.#.#
+O....#....
+OO....OO..
+#OO..###..
+#OO.O#...O"
+    )]
+    #[case(
+        Direction::West,
+        "O....#....
+OOO.#....#
+.....##...
+OO.#OO....
+OO......#.
+O.#O...#.#
+O....#OO..
+O.........
+#....###..
+#OO..#...."
+    )]
+    #[case(
+        Direction::East,
+        "....O#....
+.OOO#....#
+.....##...
+.OO#....OO
+......OO#.
+.O#...O#.#
+....O#..OO
+.........O
+#....###..
+#..OO#...."
+    )]
+    fn tilt(#[case] direction: Direction, #[case] expected: &str) {
+        let mut platform = Platform::new(EXAMPLE);
+
+        platform.tilt(direction);
+
+        assert_eq!(platform.to_string(), expected);
+    }
+
     #[rstest]
     #[case("
 O....#....
@@ -63,18 +247,9 @@ O.#..O.#.#
 .......O..
 #....###..
 #OO..#....".trim(), 136)]
-    fn base_example(#[case] input: &str, #[case] expected: u32) {
+    fn base_example(#[case] input: &str, #[case] expected: usize) {
         let result = process(input);
 
         assert_eq!(result, expected);
     }
-
-    #[test]
-    fn real_input() {
-        let input = include_str!("./input.txt");
-
-        let result = process(input);
-
-        assert_eq!(result, 0);
-    }
 }
@@ -1,44 +1,85 @@
-use itertools::Itertools;
-use std::collections::BTreeSet;
-use std::iter;
-
-use nom::{
-    bytes::complete::{tag, take_until},
-    character::complete::{self, alpha1, alphanumeric1, digit1, line_ending, newline, space1},
-    combinator::rest,
-    multi::separated_list1,
-    sequence::{pair, preceded, separated_pair, terminated},
-    IResult,
-};
-
-fn parse(input: &str) -> IResult<&str, u32> {
-    Ok((input, 0))
+pub use super::pt1::parse_input;
+
+use super::pt1::Platform;
+use crate::cycle::find_cycle;
+
+const SPIN_CYCLES: usize = 1_000_000_000;
+
+/// The platform after `cycles` spin cycles, skipping over every full loop.
+fn spin(platform: Platform, cycles: usize) -> Platform {
+    let spin_once = |platform: &Platform| {
+        let mut next = platform.clone();
+        next.spin_cycle();
+        next
+    };
+
+    let cycle = find_cycle(platform.clone(), spin_once);
+
+    (0..cycle.equivalent_step(cycles)).fold(platform, |platform, _| spin_once(&platform))
 }
 
-fn process(input: &str) -> u32 {
-    0
+pub fn solve(platform: &Platform) -> usize {
+    spin(platform.clone(), SPIN_CYCLES).north_load()
+}
+
+pub fn process(input: &str) -> usize {
+    solve(&parse_input(input))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rstest::*;
 
-    #[test]
-    fn base_example() {
-        let input = r#"
-"#;
+    const EXAMPLE: &str = "O....#....
+O.OO#....#
+.....##...
+OO.#O....O
+.O.....O#.
+O.#..O.#.#
+..O..#O..O
+.......O..
+#....###..
+#OO..#....";
 
-        let result = process(input);
+    #[rstest]
+    #[case(
+        1,
+        ".....#....
+....#...O#
+...OO##...
+.OO#......
+.....OOO#.
+.O#...O#.#
+....O#....
+......OOOO
+#...O###..
+#..OO#...."
+    )]
+    #[case(
+        3,
+        ".....#....
+....#...O#
+.....##...
+..O#......
+.....OOO#.
+.O#...O#.#
+....O#...O
+.......OOO
+#...O###.O
+#.OOO#...O"
+    )]
+    fn spin_cycles(#[case] cycles: usize, #[case] expected: &str) {
+        let result = spin(Platform::new(EXAMPLE), cycles);
 
-        assert_eq!(result, 0);
+        assert_eq!(result.to_string(), expected);
     }
 
-    #[test]
-    fn real_input() {
-        let input = include_str!("./input.txt");
-
+    #[rstest]
+    #[case(EXAMPLE, 64)]
+    fn base_example(#[case] input: &str, #[case] expected: usize) {
         let result = process(input);
 
-        assert_eq!(result, 0);
+        assert_eq!(result, expected);
     }
 }
@@ -1,68 +1,163 @@
-fn process(input: &str) -> u32 {
-    let lines = input.lines();
-
-    let sum_valid_games = lines
-        .map(|line| {
-            let game: Vec<&str> = line.split(':').collect();
-
-            match game[..] {
-                [gameinfo, drafts] => {
-                    let game_number: String = gameinfo.chars().filter(|c| c.is_numeric()).collect();
-                    let drafts: Vec<&str> = drafts.split(';').collect();
-                    let mut valid = true;
-
-                    for draft in &drafts {
-                        let parsed_drafts: Vec<Vec<&str>> = draft
-                            .split(',')
-                            .map(|x| x.trim().split(' ').collect())
-                            .collect();
-
-                        for d in parsed_drafts {
-                            match d[..] {
-                                [number, color] => {
-                                    let number =
-                                        number.parse::<u32>().expect("should be a number!");
-                                    match color {
-                                        "blue" if number > 14 => valid = false,
-                                        "green" if number > 13 => valid = false,
-                                        "red" if number > 12 => valid = false,
-                                        _ => valid = true,
-                                    }
-                                }
-                                _ => panic!("invalid input"),
-                            }
-                            if !valid {
-                                break;
-                            }
-                        }
-
-                        if !valid {
-                            break;
-                        }
-                    }
-
-                    if valid {
-                        game_number.parse::<u32>().expect("should be a number!")
-                    } else {
-                        0
-                    }
-                }
-                _ => panic!("invalid input"),
-            }
+use core::fmt;
+
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{self, alpha1, line_ending, space1},
+    combinator::{all_consuming, cut, map_res, opt},
+    multi::separated_list1,
+    sequence::{delimited, preceded, separated_pair, terminated},
+    IResult,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Colour {
+    Red,
+    Green,
+    Blue,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct UnknownColour(pub String);
+
+impl fmt::Display for UnknownColour {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown cube colour {:?}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownColour {}
+
+impl TryFrom<&str> for Colour {
+    type Error = UnknownColour;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "red" => Ok(Colour::Red),
+            "green" => Ok(Colour::Green),
+            "blue" => Ok(Colour::Blue),
+            _ => Err(UnknownColour(value.to_string())),
+        }
+    }
+}
+
+/// Cubes shown in a single handful.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Draw {
+    pub red: u32,
+    pub green: u32,
+    pub blue: u32,
+}
+
+/// How many cubes of each colour the bag holds.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BagLimits {
+    pub red: u32,
+    pub green: u32,
+    pub blue: u32,
+}
+
+pub const PUZZLE_BAG: BagLimits = BagLimits {
+    red: 12,
+    green: 13,
+    blue: 14,
+};
+
+impl BagLimits {
+    pub fn power(&self) -> u32 {
+        self.red * self.green * self.blue
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    pub id: u32,
+    pub draws: Vec<Draw>,
+}
+
+impl Game {
+    pub fn is_possible(&self, limits: &BagLimits) -> bool {
+        self.draws.iter().all(|draw| {
+            draw.red <= limits.red && draw.green <= limits.green && draw.blue <= limits.blue
         })
-        .sum();
+    }
 
-    return sum_valid_games;
+    /// Fewest cubes of each colour the bag could have held for this game.
+    pub fn minimum_bag(&self) -> BagLimits {
+        self.draws
+            .iter()
+            .fold(BagLimits::default(), |bag, draw| BagLimits {
+                red: bag.red.max(draw.red),
+                green: bag.green.max(draw.green),
+                blue: bag.blue.max(draw.blue),
+            })
+    }
+}
+
+fn cubes(input: &str) -> IResult<&str, (u32, Colour)> {
+    separated_pair(
+        complete::u32,
+        space1,
+        cut(map_res(alpha1, Colour::try_from)),
+    )(input)
+}
+
+fn draw(input: &str) -> IResult<&str, Draw> {
+    let (input, cubes) = separated_list1(tag(", "), cubes)(input)?;
+
+    let draw = cubes
+        .into_iter()
+        .fold(Draw::default(), |mut draw, (count, colour)| {
+            match colour {
+                Colour::Red => draw.red += count,
+                Colour::Green => draw.green += count,
+                Colour::Blue => draw.blue += count,
+            }
+            draw
+        });
+
+    Ok((input, draw))
+}
+
+fn game(input: &str) -> IResult<&str, Game> {
+    let (input, id) = delimited(tag("Game "), complete::u32, tag(": "))(input)?;
+    let (input, draws) = separated_list1(tag("; "), draw)(input)?;
+
+    Ok((input, Game { id, draws }))
+}
+
+pub fn parse(input: &str) -> IResult<&str, Vec<Game>> {
+    all_consuming(terminated(
+        separated_list1(line_ending, game),
+        opt(line_ending),
+    ))(input)
+}
+
+pub fn parse_input(input: &str) -> Vec<Game> {
+    let (_, games) = parse(input).expect("should parse");
+
+    games
+}
+
+pub fn solve(games: &[Game]) -> u32 {
+    games
+        .iter()
+        .filter(|game| game.is_possible(&PUZZLE_BAG))
+        .map(|game| game.id)
+        .sum()
+}
+
+pub fn process(input: &str) -> u32 {
+    solve(&parse_input(input))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rstest::*;
 
     #[test]
     fn base_example() {
-        let result = 2 + 2;
-
         let input = r#"Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
 Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
 Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
@@ -75,14 +170,41 @@ Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green"#;
     }
 
     #[test]
-    fn real_input() {
-        let result = 2 + 2;
+    fn parse_game() {
+        let (_, games) = parse("Game 12: 3 blue, 4 red; 2 green").unwrap();
+
+        assert_eq!(
+            games,
+            vec![Game {
+                id: 12,
+                draws: vec![
+                    Draw {
+                        red: 4,
+                        green: 0,
+                        blue: 3
+                    },
+                    Draw {
+                        red: 0,
+                        green: 2,
+                        blue: 0
+                    },
+                ],
+            }]
+        );
+    }
 
-        let input = include_str!("./input.txt");
+    #[test]
+    fn unknown_colour() {
+        assert!(parse("Game 1: 3 blue, 4 purple; 2 green").is_err());
+    }
 
-        let result = process(input);
+    #[rstest]
+    #[case(BagLimits { red: 20, green: 13, blue: 6 }, true)]
+    #[case(BagLimits { red: 19, green: 13, blue: 6 }, false)]
+    #[case(PUZZLE_BAG, false)]
+    fn possible_with_limits(#[case] limits: BagLimits, #[case] expected: bool) {
+        let (_, games) = parse("Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green").unwrap();
 
-        assert_eq!(result, 2600);
+        assert_eq!(games[0].is_possible(&limits), expected);
     }
 }
-
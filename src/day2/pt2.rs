@@ -1,45 +1,14 @@
-fn process(input: &str) -> u32 {
-    let lines = input.lines();
+pub use super::pt1::parse_input;
 
-    let sum_valid_games = lines.map(|line| {
-        let game: Vec<&str> = line.split(":").collect();
+use super::pt1::{parse, BagLimits, Game};
 
-        match game[..] {
-            [gameinfo, drafts] => {
-                let game_number: String = gameinfo.chars().filter(|c| c.is_numeric()).collect();
-                let drafts: Vec<&str> = drafts.split(";").collect();
-                let mut blue = 0;
-                let mut green = 0;
-                let mut red = 0;
-
-                for draft in &drafts {
-                    let parsed_drafts: Vec<Vec<&str>> = draft.split(",").map(|x| x.trim().split(" ").collect()).collect();
-
-                    for d in parsed_drafts {
-                        match d[..] {
-                            [number, color] => {
-                                let number = number.parse::<u32>().expect("should be a number!");
-                                match color {
-                                    "blue" if number > blue => blue = number,
-                                    "green" if number > green => green = number,
-                                    "red" if number > red => red = number,
-                                    _ => ()
-                                }
-                            }
-                            _ => panic!("invalid input"),
-                        }
-                    }
-                }
-
-                blue * green * red
-            }
-            _ => panic!("invalid input"),
-        }
-    }).sum();
-
-    return sum_valid_games;
+pub fn solve(games: &[Game]) -> u32 {
+    games.iter().map(|game| game.minimum_bag().power()).sum()
 }
 
+pub fn process(input: &str) -> u32 {
+    solve(&parse_input(input))
+}
 
 #[cfg(test)]
 mod tests {
@@ -47,30 +16,28 @@ mod tests {
 
     #[test]
     fn base_example() {
-        let result = 2 + 2;
-
         let input = r#"Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
 Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
 Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
 Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
 Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green"#;
 
-
         let result = process(input);
 
         assert_eq!(result, 2286);
     }
 
-
     #[test]
-    fn real_input() {
-        let result = 2 + 2;
-
-        let input = include_str!("./input.txt");
-
-
-        let result = process(input);
-
-        assert_eq!(result, 86036);
+    fn minimum_bag() {
+        let (_, games) = parse("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green").unwrap();
+
+        assert_eq!(
+            games[0].minimum_bag(),
+            BagLimits {
+                red: 4,
+                green: 2,
+                blue: 6
+            }
+        );
     }
 }
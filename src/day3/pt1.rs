@@ -1,104 +1,145 @@
 use itertools::Itertools;
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashMap};
 use std::iter;
 
-#[derive(Debug)]
-struct PartNumber {
-    start: usize,
-    end: usize,
-    number: u32,
+/// A number in the schematic, spanning columns `start..=end` of `row`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PartNumber {
+    pub row: usize,
+    pub start: usize,
+    pub end: usize,
+    pub number: u32,
 }
 
-fn process(input: &str) -> u32 {
-    let lines = input.lines();
-
-    let start = iter::once("");
-    let end = iter::once("");
-    let lines = start.chain(lines).chain(end);
-
-    let tuples = lines.tuple_windows::<(_, _, _)>();
-    let mut sum_part_numbers = 0;
-
-    for tuple in tuples {
-        let (line1, current_line, line3) = tuple;
-
-        let symbols = line1
-            .char_indices()
-            .filter(|(i, c)| c != &'.' && !c.is_numeric());
-        let symbols2 = current_line
-            .char_indices()
-            .filter(|(i, c)| c != &'.' && !c.is_numeric());
-        let symbols3 = line3
-            .char_indices()
-            .filter(|(i, c)| c != &'.' && !c.is_numeric());
-
-        let symbols_index = symbols
-            .chain(symbols2)
-            .chain(symbols3)
-            .map(|(i, c)| i)
-            .collect::<BTreeSet<usize>>();
-
-        let mut temp_number = "".to_string();
-        let mut start_index = 0;
-        let mut it = current_line.char_indices().peekable();
-        let mut part_numbers: Vec<PartNumber> = vec![];
-
-        while let Some((i, c)) = it.next() {
-            if c.is_numeric() {
-                temp_number = temp_number + &c.to_string();
-                if start_index == 0 {
-                    start_index = i;
-                }
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Symbol {
+    pub row: usize,
+    pub column: usize,
+    pub symbol: char,
+}
 
-                match it.peek() {
-                    None if start_index != 0 => {
-                        part_numbers.push(PartNumber {
-                            start: start_index,
-                            end: i,
-                            number: temp_number.parse::<u32>().expect("should be a number!"),
-                        });
+/// Numbers and symbols of an engine schematic, indexed by the cells they cover
+/// so adjacency checks only look at neighbouring cells.
+#[derive(Debug, Default)]
+pub struct Schematic {
+    numbers: Vec<PartNumber>,
+    symbols: Vec<Symbol>,
+    number_at: HashMap<(usize, usize), usize>,
+    symbol_at: HashMap<(usize, usize), usize>,
+}
 
-                        start_index = 0;
-                        temp_number = "".to_string();
-                    }
-                    Some((_, next)) if !next.is_numeric() => {
-                        part_numbers.push(PartNumber {
-                            start: start_index,
-                            end: i,
-                            number: temp_number.parse::<u32>().expect("should be a number!"),
+impl Schematic {
+    pub fn new(input: &str) -> Self {
+        let mut schematic = Schematic::default();
+
+        for (row, line) in input.lines().enumerate() {
+            let mut columns = line.char_indices().peekable();
+
+            while let Some((column, c)) = columns.next() {
+                match c {
+                    '.' => {}
+                    c if c.is_ascii_digit() => {
+                        let mut end = column;
+                        while let Some((next, _)) =
+                            columns.next_if(|(_, next)| next.is_ascii_digit())
+                        {
+                            end = next;
+                        }
+
+                        schematic.push_number(PartNumber {
+                            row,
+                            start: column,
+                            end,
+                            number: line[column..=end].parse().expect("should be a number!"),
                         });
-
-                        start_index = 0;
-                        temp_number = "".to_string();
                     }
-                    _ => (),
+                    symbol => schematic.push_symbol(Symbol {
+                        row,
+                        column,
+                        symbol,
+                    }),
                 }
             }
         }
 
-        let filtered_parts: Vec<_> = part_numbers
-            .into_iter()
-            .filter(|part_number| {
-                (part_number.start - 1..=part_number.end + 1).any(|i| symbols_index.contains(&i))
-            })
-            .collect();
+        schematic
+    }
+
+    fn push_number(&mut self, number: PartNumber) {
+        for column in number.start..=number.end {
+            self.number_at
+                .insert((number.row, column), self.numbers.len());
+        }
+        self.numbers.push(number);
+    }
+
+    fn push_symbol(&mut self, symbol: Symbol) {
+        self.symbol_at
+            .insert((symbol.row, symbol.column), self.symbols.len());
+        self.symbols.push(symbol);
+    }
+
+    pub fn numbers(&self) -> &[PartNumber] {
+        &self.numbers
+    }
 
-        sum_part_numbers += filtered_parts
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    /// Symbols touching `number`, diagonals included.
+    pub fn adjacent_symbols(&self, number: &PartNumber) -> Vec<&Symbol> {
+        neighbourhood(number.row, number.start, number.end)
+            .filter_map(|cell| self.symbol_at.get(&cell))
+            .map(|&index| &self.symbols[index])
+            .collect()
+    }
+
+    /// Distinct numbers touching `symbol`, diagonals included, in reading order.
+    pub fn adjacent_numbers(&self, symbol: &Symbol) -> Vec<&PartNumber> {
+        neighbourhood(symbol.row, symbol.column, symbol.column)
+            .filter_map(|cell| self.number_at.get(&cell))
+            .copied()
+            .unique()
+            .map(|index| &self.numbers[index])
+            .collect()
+    }
+
+    /// Numbers next to at least one symbol.
+    pub fn part_numbers(&self) -> impl Iterator<Item = &PartNumber> {
+        self.numbers
             .iter()
-            .map(|part_number| part_number.number)
-            .sum::<u32>();
+            .filter(|number| !self.adjacent_symbols(number).is_empty())
     }
-    sum_part_numbers
+}
+
+/// Cells of the rectangle around `row` and `start..=end`, clipped at the top
+/// and left edges.
+fn neighbourhood(row: usize, start: usize, end: usize) -> impl Iterator<Item = (usize, usize)> {
+    (row.saturating_sub(1)..=row + 1)
+        .cartesian_product(start.saturating_sub(1)..=end + 1)
+        .filter(move |&(r, c)| r != row || c < start || c > end)
+}
+
+pub fn parse_input(input: &str) -> Schematic {
+    Schematic::new(input)
+}
+
+pub fn solve(schematic: &Schematic) -> u32 {
+    schematic.part_numbers().map(|x| x.number).sum()
+}
+
+pub fn process(input: &str) -> u32 {
+    solve(&parse_input(input))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rstest::*;
 
     #[test]
     fn base_example() {
-        let result = 2 + 2;
-
         let input = r#"467..114..
 ...*......
 ..35..633.
@@ -117,8 +158,6 @@ mod tests {
 
     #[test]
     fn another_example() {
-        let result = 2 + 2;
-
         let input = r#".......5......
 ..7*..*.....4*
 ...*13*......9
@@ -138,14 +177,37 @@ mod tests {
         assert_eq!(result, 62);
     }
 
-    #[test]
-    fn real_input() {
-        let result = 2 + 2;
+    #[rstest]
+    #[case("12*\n...", 12)]
+    #[case("*12\n...", 12)]
+    #[case("12.\n..#", 12)]
+    #[case("#..\n...\n.12", 0)]
+    #[case(".7.\n...\n.$.", 0)]
+    #[case("3.4\n.+.\n5.6", 18)]
+    fn numbers_at_edges(#[case] input: &str, #[case] expected: u32) {
+        let result = process(input);
 
-        let input = include_str!("./input.txt");
+        assert_eq!(result, expected);
+    }
 
-        let result = process(input);
+    #[test]
+    fn adjacency_queries() {
+        let schematic = Schematic::new("467..114..\n...*......\n..35..633.");
+        let star = schematic.symbols()[0];
+
+        let numbers: Vec<u32> = schematic
+            .adjacent_numbers(&star)
+            .iter()
+            .map(|x| x.number)
+            .collect();
 
-        assert_eq!(result, 520019);
+        assert_eq!(numbers, vec![467, 35]);
+        assert_eq!(
+            schematic.adjacent_symbols(&schematic.numbers()[0]),
+            vec![&star]
+        );
+        assert!(schematic
+            .adjacent_symbols(&schematic.numbers()[1])
+            .is_empty());
     }
 }
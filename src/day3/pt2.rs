@@ -1,104 +1,34 @@
-use itertools::Itertools;
-use std::collections::BTreeSet;
-use std::iter;
-
-#[derive(Debug)]
-struct PartNumber {
-    start: usize,
-    end: usize,
-    number: u32,
+pub use super::pt1::parse_input;
+
+use super::pt1::{Schematic, Symbol};
+
+/// Product of the two numbers next to each `*` that touches exactly two.
+fn gear_ratios(schematic: &Schematic) -> impl Iterator<Item = u32> + '_ {
+    schematic
+        .symbols()
+        .iter()
+        .filter(|symbol| symbol.symbol == '*')
+        .filter_map(|symbol| match schematic.adjacent_numbers(symbol)[..] {
+            [a, b] => Some(a.number * b.number),
+            _ => None,
+        })
 }
 
-fn process(input: &str) -> u32 {
-    let lines = input.lines();
-
-    let start = iter::once("");
-    let end = iter::once("");
-    let lines = start.chain(lines).chain(end);
-
-    let tuples = lines.tuple_windows::<(_, _, _)>();
-    let mut sum_part_numbers = 0;
-
-    for tuple in tuples {
-        let (line1, current_line, line3) = tuple;
-
-        let symbols = line1
-            .char_indices()
-            .filter(|(i, c)| c != &'.' && !c.is_numeric());
-        let symbols2 = current_line
-            .char_indices()
-            .filter(|(i, c)| c != &'.' && !c.is_numeric());
-        let symbols3 = line3
-            .char_indices()
-            .filter(|(i, c)| c != &'.' && !c.is_numeric());
-
-        let symbols_index = symbols
-            .chain(symbols2)
-            .chain(symbols3)
-            .map(|(i, c)| i)
-            .collect::<BTreeSet<usize>>();
-
-        let mut temp_number = "".to_string();
-        let mut start_index = 0;
-        let mut it = current_line.char_indices().peekable();
-        let mut part_numbers: Vec<PartNumber> = vec![];
-
-        while let Some((i, c)) = it.next() {
-            if c.is_numeric() {
-                temp_number = temp_number + &c.to_string();
-                if start_index == 0 {
-                    start_index = i;
-                }
-
-                match it.peek() {
-                    None if start_index != 0 => {
-                        part_numbers.push(PartNumber {
-                            start: start_index,
-                            end: i,
-                            number: temp_number.parse::<u32>().expect("should be a number!"),
-                        });
-
-                        start_index = 0;
-                        temp_number = "".to_string();
-                    }
-                    Some((_, next)) if !next.is_numeric() => {
-                        part_numbers.push(PartNumber {
-                            start: start_index,
-                            end: i,
-                            number: temp_number.parse::<u32>().expect("should be a number!"),
-                        });
-
-                        start_index = 0;
-                        temp_number = "".to_string();
-                    }
-                    _ => (),
-                }
-            }
-        }
-
-        let filtered_parts: Vec<_> = part_numbers
-            .into_iter()
-            .filter(|part_number| {
-                (part_number.start - 1..=part_number.end + 1).any(|i| symbols_index.contains(&i))
-            })
-            .collect();
+pub fn solve(schematic: &Schematic) -> u32 {
+    gear_ratios(schematic).sum()
+}
 
-        sum_part_numbers += filtered_parts
-            .iter()
-            .map(|part_number| part_number.number)
-            .sum::<u32>();
-    }
-    sum_part_numbers
+pub fn process(input: &str) -> u32 {
+    solve(&parse_input(input))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rstest::*;
 
     #[test]
     fn base_example() {
-        let result = 2 + 2;
-
         let input = r#"467..114..
 ...*......
 ..35..633.
@@ -112,40 +42,21 @@ mod tests {
 
         let result = process(input);
 
-        assert_eq!(result, 4361);
-    }
-
-    #[test]
-    fn another_example() {
-        let result = 2 + 2;
-
-        let input = r#".......5......
-..7*..*.....4*
-...*13*......9
-.......15.....
-..............
-..............
-..............
-..............
-..............
-..............
-21............
-...*9.........
-"#;
-
-        let result = process(input);
-
-        assert_eq!(result, 62);
+        assert_eq!(result, 467835);
     }
 
-    #[test]
-    fn real_input() {
-        let result = 2 + 2;
-
-        let input = include_str!("./input.txt");
-
+    #[rstest]
+    #[case("2*3", 6)]
+    #[case("*2\n3.", 6)]
+    #[case("12.\n..*\n.34", 408)]
+    #[case("..12\n*...\n.34.", 0)]
+    #[case("5..\n.*.\n..7", 35)]
+    #[case("1.2\n.*.\n3..", 0)]
+    #[case("10..\n.+*.\n..20", 200)]
+    #[case("4*\n.5\n*6", 20 + 30)]
+    fn gears(#[case] input: &str, #[case] expected: u32) {
         let result = process(input);
 
-        assert_eq!(result, 520019);
+        assert_eq!(result, expected);
     }
 }
@@ -1,33 +1,65 @@
 use std::collections::HashSet;
+use std::ops::Range;
 
+use itertools::Itertools;
 use nom::{
     bytes::complete::tag,
     character::complete::{self, alpha1, digit1, line_ending, space1},
-    combinator::cut,
+    combinator::{cut, map, verify},
     multi::separated_list1,
     sequence::{preceded, separated_pair},
     IResult,
 };
 
 #[derive(Debug)]
-struct Card<'a> {
-    card_id: &'a str,
+pub struct Card {
+    card_id: u32,
     winning_numbers: HashSet<u32>,
     card_numbers: HashSet<u32>,
 }
 
-fn parse_numbers(input: &str) -> IResult<&str, HashSet<u32>> {
-    let (input, numbers) = separated_list1(space1, complete::u32)(input)?;
+impl Card {
+    pub fn card_id(&self) -> u32 {
+        self.card_id
+    }
+
+    pub fn matches(&self) -> u32 {
+        self.card_numbers
+            .intersection(&self.winning_numbers)
+            .count() as u32
+    }
+
+    /// One point for the first match, doubled for every match after it, or
+    /// `None` when that no longer fits in a `u32`.
+    pub fn points(&self) -> Option<u32> {
+        match self.matches() {
+            0 => Some(0),
+            matches => 1_u32.checked_shl(matches - 1),
+        }
+    }
+
+    /// Ids of the cards this one wins a copy of, cut short where the end of
+    /// the range would overflow a `u32`.
+    pub fn copies_won(&self) -> Range<u32> {
+        let first = self.card_id.saturating_add(1);
 
-    let mut n = HashSet::new();
-    for num in numbers {
-        n.insert(num);
+        first..first.saturating_add(self.matches())
     }
-    Ok((input, n))
+}
+
+/// A list of numbers where each one shows up only once.
+fn parse_numbers(input: &str) -> IResult<&str, HashSet<u32>> {
+    map(
+        verify(
+            separated_list1(space1, complete::u32),
+            |numbers: &Vec<u32>| numbers.iter().all_unique(),
+        ),
+        HashSet::from_iter,
+    )(input)
 }
 
 fn card(input: &str) -> IResult<&str, Card> {
-    let (input, card_id) = preceded(tag("Card"), preceded(space1, digit1))(input)?;
+    let (input, card_id) = preceded(tag("Card"), preceded(space1, complete::u32))(input)?;
     let (input, (winning_numbers, card_numbers)) = preceded(
         preceded(tag(":"), space1),
         separated_pair(
@@ -46,28 +78,34 @@ fn card(input: &str) -> IResult<&str, Card> {
     Ok((input, card))
 }
 
-fn parse_cards(input: &str) -> IResult<&str, Vec<Card>> {
+pub fn parse_cards(input: &str) -> IResult<&str, Vec<Card>> {
     let (input, cards) = cut(separated_list1(line_ending, card))(input)?;
 
     Ok((input, cards))
 }
 
-fn process(input: &str) -> u32 {
-    let (input, cards) = parse_cards(input).expect("should parse");
+pub fn parse_input(input: &str) -> Vec<Card> {
+    let (_, cards) = parse_cards(input).expect("should parse");
+
+    cards
+}
 
-    let matching_numbers: u32 = cards
+pub fn solve(cards: &[Card]) -> u32 {
+    cards
         .iter()
-        .map(|c| c.card_numbers.intersection(&c.winning_numbers).count())
-        .filter(|c| c > &0_usize)
-        .map(|c| (0..c - 1).fold(1, |x, y| x * 2))
-        .sum();
+        .map(Card::points)
+        .sum::<Option<u32>>()
+        .expect("points should fit in a u32")
+}
 
-    matching_numbers
+pub fn process(input: &str) -> u32 {
+    solve(&parse_input(input))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rstest::*;
 
     #[test]
     fn base_example() {
@@ -86,13 +124,39 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
         assert_eq!(result, 13);
     }
 
-    #[test]
-    // #[ignore]
-    fn real_input() {
-        let input = include_str!("./input.txt");
+    #[rstest]
+    #[case("Card 1: 1 2 3 | 4 5 6", 0, Some(0), 2..2)]
+    #[case("Card 2: 1 2 3 | 3 4 5", 1, Some(1), 3..4)]
+    #[case("Card 3: 1 2 3 | 1 2 3", 3, Some(4), 4..7)]
+    #[case("Card 4294967294: 1 2 | 1 2", 2, Some(2), u32::MAX..u32::MAX)]
+    fn card_scoring(
+        #[case] input: &str,
+        #[case] matches: u32,
+        #[case] points: Option<u32>,
+        #[case] copies_won: Range<u32>,
+    ) {
+        let (_, cards) = parse_cards(input).unwrap();
+
+        assert_eq!(cards[0].matches(), matches);
+        assert_eq!(cards[0].points(), points);
+        assert_eq!(cards[0].copies_won(), copies_won);
+    }
 
-        let result = process(input);
+    #[rstest]
+    #[case(32, Some(1 << 31))]
+    #[case(33, None)]
+    fn points_overflow(#[case] matches: u32, #[case] expected: Option<u32>) {
+        let numbers = (1..=matches).join(" ");
+        let input = format!("Card 1: {numbers} | {numbers}");
+        let (_, cards) = parse_cards(&input).unwrap();
+
+        assert_eq!(cards[0].points(), expected);
+    }
 
-        assert_eq!(result, 20407);
+    #[rstest]
+    #[case("Card 1: 1 2 1 | 3 4")]
+    #[case("Card 1: 1 2 | 3 4 3")]
+    fn duplicate_numbers(#[case] input: &str) {
+        assert!(parse_cards(input).is_err());
     }
 }
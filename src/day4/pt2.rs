@@ -1,73 +1,88 @@
-use std::collections::HashSet;
-
-use nom::{
-    bytes::complete::tag,
-    character::complete::{self, alpha1, digit1, line_ending, space1},
-    combinator::cut,
-    multi::separated_list1,
-    sequence::{preceded, separated_pair},
-    IResult,
-};
-
-#[derive(Debug)]
-struct Card<'a> {
-    card_id: &'a str,
-    winning_numbers: HashSet<u32>,
-    card_numbers: HashSet<u32>,
+use core::fmt;
+use std::collections::BTreeMap;
+
+pub use super::pt1::parse_input;
+
+use super::pt1::{parse_cards, Card};
+
+#[derive(Debug, PartialEq, Eq)]
+enum CascadeError {
+    DuplicateCard(u32),
+    OutOfOrder {
+        previous: u32,
+        card_id: u32,
+    },
+    /// The count of cards passes what a `u32` holds at this card.
+    Overflow(u32),
 }
 
-fn parse_numbers(input: &str) -> IResult<&str, HashSet<u32>> {
-    let (input, numbers) = separated_list1(space1, complete::u32)(input)?;
-
-    let mut n = HashSet::new();
-    for num in numbers {
-        n.insert(num);
+impl fmt::Display for CascadeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CascadeError::DuplicateCard(card_id) => write!(f, "card {card_id} appears twice"),
+            CascadeError::OutOfOrder { previous, card_id } => {
+                write!(f, "card {card_id} comes after card {previous}")
+            }
+            CascadeError::Overflow(card_id) => {
+                write!(f, "counting cards overflows a u32 at card {card_id}")
+            }
+        }
     }
-    Ok((input, n))
 }
 
-fn card(input: &str) -> IResult<&str, Card> {
-    let (input, card_id) = preceded(tag("Card"), preceded(space1, digit1))(input)?;
-    let (input, (winning_numbers, card_numbers)) = preceded(
-        preceded(tag(":"), space1),
-        separated_pair(
-            parse_numbers,
-            preceded(space1, preceded(tag("|"), space1)),
-            parse_numbers,
-        ),
-    )(input)?;
-
-    let card = Card {
-        card_id,
-        winning_numbers,
-        card_numbers,
-    };
-
-    Ok((input, card))
-}
+impl std::error::Error for CascadeError {}
+
+/// Total number of cards held once every card has won its copies. A card with
+/// `n` matches wins one copy of each of the next `n` card ids, once per copy
+/// of it that is held.
+fn count_cards(cards: &[Card]) -> Result<u32, CascadeError> {
+    let mut copies: BTreeMap<u32, u32> = BTreeMap::new();
+
+    for card in cards {
+        match copies.last_key_value() {
+            Some((&previous, _)) if previous == card.card_id() => {
+                return Err(CascadeError::DuplicateCard(card.card_id()))
+            }
+            Some((&previous, _)) if previous > card.card_id() => {
+                return Err(CascadeError::OutOfOrder {
+                    previous,
+                    card_id: card.card_id(),
+                })
+            }
+            _ => copies.insert(card.card_id(), 1),
+        };
+    }
 
-fn parse_cards(input: &str) -> IResult<&str, Vec<Card>> {
-    let (input, cards) = cut(separated_list1(line_ending, card))(input)?;
+    for card in cards {
+        let held = copies[&card.card_id()];
 
-    Ok((input, cards))
-}
+        for (&card_id, count) in copies.range_mut(card.copies_won()) {
+            *count = count
+                .checked_add(held)
+                .ok_or(CascadeError::Overflow(card_id))?;
+        }
+    }
 
-fn process(input: &str) -> u32 {
-    let (input, cards) = parse_cards(input).expect("should parse");
+    copies.iter().try_fold(0_u32, |total, (&card_id, count)| {
+        total
+            .checked_add(*count)
+            .ok_or(CascadeError::Overflow(card_id))
+    })
+}
 
-    let matching_numbers: u32 = cards
-        .iter()
-        .map(|c| c.card_numbers.intersection(&c.winning_numbers).count())
-        .filter(|c| c > &0_usize)
-        .map(|c| (0..c - 1).fold(1, |x, y| x * 2))
-        .sum();
+pub fn solve(cards: &[Card]) -> u32 {
+    count_cards(cards).expect("cards should be in order and countable")
+}
 
-    matching_numbers
+pub fn process(input: &str) -> u32 {
+    solve(&parse_input(input))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use itertools::Itertools;
+    use rstest::*;
 
     #[test]
     fn base_example() {
@@ -83,16 +98,45 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
 
         let result = process(input);
 
-        assert_eq!(result, 13);
+        assert_eq!(result, 30);
+    }
+
+    #[rstest]
+    #[case("Card 1: 1 | 1\nCard 1: 2 | 3", CascadeError::DuplicateCard(1))]
+    #[case(
+        "Card 2: 1 | 1\nCard 1: 2 | 3",
+        CascadeError::OutOfOrder { previous: 2, card_id: 1 }
+    )]
+    fn invalid_card_ids(#[case] input: &str, #[case] expected: CascadeError) {
+        let (_, cards) = parse_cards(input).unwrap();
+
+        assert_eq!(count_cards(&cards), Err(expected));
     }
 
     #[test]
-    // #[ignore]
-    fn real_input() {
-        let input = include_str!("./input.txt");
+    fn copies_follow_card_ids() {
+        let input = "Card 3: 1 2 | 1 2\nCard 5: 3 | 3\nCard 6: 4 | 5";
+        let (_, cards) = parse_cards(input).unwrap();
 
-        let result = process(input);
+        assert_eq!(count_cards(&cards), Ok(1 + 2 + 3));
+    }
 
-        assert_eq!(result, 20407);
+    #[test]
+    fn copies_overflow() {
+        // Card `n` wins a copy of every later card, so it ends up with
+        // 2^(n - 1) copies.
+        let input = (1..=34)
+            .map(|card_id| {
+                let numbers = (1..=34 - card_id).map(|x| x.to_string()).join(" ");
+                match numbers.as_str() {
+                    "" => format!("Card {card_id}: 1 | 2"),
+                    numbers => format!("Card {card_id}: {numbers} | {numbers}"),
+                }
+            })
+            .join("\n");
+        let (_, cards) = parse_cards(&input).unwrap();
+
+        assert_eq!(count_cards(&cards[..32]), Ok(u32::MAX));
+        assert_eq!(count_cards(&cards), Err(CascadeError::Overflow(33)));
     }
 }
@@ -18,11 +18,12 @@ struct Coordinate {
 }
 
 impl Coordinate {
-    fn source_end(&self) -> u32 {
-        self.source_start + self.offset
+    /// One past the last source number this maps; `offset` is the length.
+    fn source_end(&self) -> u64 {
+        u64::from(self.source_start) + u64::from(self.offset)
     }
     fn within_range(&self, seed: &u32) -> bool {
-        seed >= &self.source_start && seed <= &self.source_end()
+        seed >= &self.source_start && u64::from(*seed) < self.source_end()
     }
 
     fn next_location(&self, seed: &u32) -> Option<u32> {
@@ -53,7 +54,7 @@ impl<'a> Map<'a> {
 }
 
 #[derive(Debug)]
-struct Almanac<'a> {
+pub struct Almanac<'a> {
     seeds: Vec<u32>,
     maps: Vec<Map<'a>>,
 }
@@ -74,7 +75,7 @@ impl<'a> Almanac<'a> {
     }
 }
 
-fn parse_maps(input: &str) -> IResult<&str, Map> {
+fn parse_maps(input: &str) -> IResult<&str, Map<'_>> {
     let (input, (source, destination)) = terminated(
         separated_pair(alphanumeric1, tag("-to-"), alphanumeric1),
         take_until("\n"),
@@ -106,7 +107,7 @@ fn parse_maps(input: &str) -> IResult<&str, Map> {
     Ok((input, map))
 }
 
-fn parse(input: &str) -> IResult<&str, Almanac> {
+fn parse(input: &str) -> IResult<&str, Almanac<'_>> {
     let (input, seeds) = preceded(
         preceded(tag("seeds:"), space1),
         separated_list1(space1, complete::u32),
@@ -120,12 +121,20 @@ fn parse(input: &str) -> IResult<&str, Almanac> {
     Ok((input, Almanac { seeds, maps }))
 }
 
-fn process(input: &str) -> u32 {
-    let (input, almanac) = parse(input).expect("parsed correctly");
+pub fn parse_input(input: &str) -> Almanac<'_> {
+    let (_, almanac) = parse(input).expect("parsed correctly");
 
+    almanac
+}
+
+pub fn solve(almanac: &Almanac) -> u32 {
     almanac.find_lowest_location()
 }
 
+pub fn process(input: &str) -> u32 {
+    solve(&parse_input(input))
+}
+
 #[cfg(test)]
 mod tests {
     use nom::Parser;
@@ -142,6 +151,7 @@ mod tests {
 
         assert_eq!(coordinate1.next_location(&78_u32), None);
         assert_eq!(coordinate1.next_location(&99_u32), Some(51));
+        assert_eq!(coordinate1.next_location(&100_u32), None);
     }
 
     #[test]
@@ -210,13 +220,4 @@ humidity-to-location map:
         let result = process(input);
         assert_eq!(result, 35);
     }
-
-    #[test]
-    fn real_input() {
-        let input = include_str!("./input.txt");
-
-        let result = process(input);
-
-        assert_eq!(result, 240320250);
-    }
 }
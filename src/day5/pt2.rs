@@ -9,6 +9,7 @@ use nom::{
 };
 use std::collections::{BTreeSet, VecDeque};
 use std::iter;
+use std::ops::Range;
 
 #[derive(Debug)]
 struct Coordinate {
@@ -18,11 +19,12 @@ struct Coordinate {
 }
 
 impl Coordinate {
-    fn source_end(&self) -> u32 {
-        self.source_start + self.offset
+    /// One past the last source number this maps; `offset` is the length.
+    fn source_end(&self) -> u64 {
+        u64::from(self.source_start) + u64::from(self.offset)
     }
     fn within_range(&self, seed: &u32) -> bool {
-        seed >= &self.source_start && seed <= &self.source_end()
+        seed >= &self.source_start && u64::from(*seed) < self.source_end()
     }
 
     fn next_location(&self, seed: &u32) -> Option<u32> {
@@ -32,6 +34,22 @@ impl Coordinate {
             None
         }
     }
+
+    /// Splits `range` into the part this maps, already mapped, and the parts
+    /// before and after it, which it leaves alone.
+    fn map_range(&self, range: Range<u64>) -> (Option<Range<u64>>, [Range<u64>; 2]) {
+        let source_start = u64::from(self.source_start);
+        let start = range.start.max(source_start);
+        let end = range.end.min(self.source_end());
+
+        if start >= end {
+            return (None, [range, 0..0]);
+        }
+
+        let dest_start = u64::from(self.dest_start);
+        let mapped = dest_start + (start - source_start)..dest_start + (end - source_start);
+        (Some(mapped), [range.start..start, end..range.end])
+    }
 }
 
 #[derive(Debug)]
@@ -50,10 +68,32 @@ impl<'a> Map<'a> {
 
         coordinate.unwrap_or(source_position)
     }
+
+    /// Where every number in `ranges` goes, a whole range at a time. Like
+    /// `next_location`, the first coordinate that covers a number maps it.
+    fn next_ranges(&self, ranges: Vec<Range<u64>>) -> Vec<Range<u64>> {
+        let mut unmapped = ranges;
+        let mut mapped = Vec::new();
+
+        for coordinate in &self.coordinates {
+            unmapped = unmapped
+                .into_iter()
+                .flat_map(|range| {
+                    let (moved, rest) = coordinate.map_range(range);
+                    mapped.extend(moved);
+                    rest
+                })
+                .filter(|range| !range.is_empty())
+                .collect();
+        }
+
+        mapped.extend(unmapped);
+        mapped
+    }
 }
 
 #[derive(Debug)]
-struct Almanac<'a> {
+pub struct Almanac<'a> {
     seeds: Vec<(u32, u32)>,
     maps: Vec<Map<'a>>,
 }
@@ -65,18 +105,31 @@ impl<'a> Almanac<'a> {
             .fold(seed, |last_location, map| map.next_location(last_location))
     }
 
-    fn find_lowest_location(&self) -> u32 {
-        self.seeds
+    /// Follows each range of seeds through the maps as ranges, splitting them
+    /// wherever a map does.
+    fn lowest_location(&self) -> u32 {
+        let seeds = self
+            .seeds
             .iter()
-            .flat_map(|(start, end)| (*start..*start + *end))
-            .map(|seed| self.find_location(seed))
-            .inspect(|x| print!("{}", x))
+            .map(|&(start, len)| u64::from(start)..u64::from(start) + u64::from(len))
+            .collect();
+
+        let locations = self
+            .maps
+            .iter()
+            .fold(seeds, |ranges, map| map.next_ranges(ranges));
+
+        let lowest = locations
+            .iter()
+            .filter(|range| !range.is_empty())
+            .map(|range| range.start)
             .min()
-            .unwrap()
+            .expect("at least one seed");
+        u32::try_from(lowest).expect("locations fit in a u32")
     }
 }
 
-fn parse_maps(input: &str) -> IResult<&str, Map> {
+fn parse_maps(input: &str) -> IResult<&str, Map<'_>> {
     let (input, (source, destination)) = terminated(
         separated_pair(alphanumeric1, tag("-to-"), alphanumeric1),
         take_until("\n"),
@@ -108,7 +161,7 @@ fn parse_maps(input: &str) -> IResult<&str, Map> {
     Ok((input, map))
 }
 
-fn parse(input: &str) -> IResult<&str, Almanac> {
+fn parse(input: &str) -> IResult<&str, Almanac<'_>> {
     let (input, seeds) = preceded(
         preceded(tag("seeds:"), space1),
         separated_list1(space1, separated_pair(complete::u32, space1, complete::u32)),
@@ -122,18 +175,80 @@ fn parse(input: &str) -> IResult<&str, Almanac> {
     Ok((input, Almanac { seeds, maps }))
 }
 
-fn process(input: &str) -> u32 {
-    let (input, almanac) = parse(input).expect("parsed correctly");
+pub fn parse_input(input: &str) -> Almanac<'_> {
+    let (_, almanac) = parse(input).expect("parsed correctly");
 
-    almanac.find_lowest_location()
+    almanac
+}
+
+pub fn solve(almanac: &Almanac) -> u32 {
+    almanac.lowest_location()
+}
+
+pub fn process(input: &str) -> u32 {
+    solve(&parse_input(input))
 }
 
 #[cfg(test)]
 mod tests {
     use nom::Parser;
+    use proptest::prelude::*;
 
     use super::*;
 
+    const CATEGORIES: [&str; 8] = [
+        "seed",
+        "soil",
+        "fertilizer",
+        "water",
+        "light",
+        "temperature",
+        "humidity",
+        "location",
+    ];
+
+    /// `dest source length` lines of one map, which may overlap.
+    fn map_lines() -> impl Strategy<Value = Vec<(u32, u32, u32)>> {
+        prop::collection::vec((0..120_u32, 0..120_u32, 1..40_u32), 1..=4)
+    }
+
+    /// An almanac with small numbers, so every seed can be followed alone.
+    fn almanac() -> impl Strategy<Value = String> {
+        let seeds = prop::collection::vec((0..100_u32, 1..30_u32), 1..=4);
+        let maps = prop::collection::vec(map_lines(), CATEGORIES.len() - 1);
+
+        (seeds, maps).prop_map(|(seeds, maps)| {
+            let seeds = seeds
+                .iter()
+                .map(|(start, len)| format!("{start} {len}"))
+                .join(" ");
+            let maps = maps
+                .iter()
+                .zip(CATEGORIES.iter().tuple_windows())
+                .map(|(lines, (source, destination))| {
+                    let lines = lines
+                        .iter()
+                        .map(|(dest, source, len)| format!("{dest} {source} {len}"))
+                        .join("\n");
+                    format!("{source}-to-{destination} map:\n{lines}")
+                })
+                .join("\n\n");
+
+            format!("seeds: {seeds}\n\n{maps}")
+        })
+    }
+
+    /// The straightforward answer, following every seed on its own.
+    fn lowest_by_trying(almanac: &Almanac) -> u32 {
+        almanac
+            .seeds
+            .iter()
+            .flat_map(|&(start, len)| start..start + len)
+            .map(|seed| almanac.find_location(seed))
+            .min()
+            .unwrap()
+    }
+
     #[test]
     fn next_location() {
         let coordinate1 = Coordinate {
@@ -214,12 +329,36 @@ humidity-to-location map:
     }
 
     #[test]
-    #[ignore]
-    fn real_input() {
-        let input = include_str!("./input.txt");
+    fn ranges_split_at_coordinates() {
+        let map = Map {
+            source: "seed",
+            destination: "soil",
+            coordinates: vec![
+                Coordinate {
+                    offset: 2,
+                    dest_start: 50,
+                    source_start: 98,
+                },
+                Coordinate {
+                    offset: 48,
+                    dest_start: 52,
+                    source_start: 50,
+                },
+            ],
+        };
 
-        let result = process(input);
+        let mut ranges = map.next_ranges(vec![40..60, 97..101]);
+        ranges.sort_by_key(|range| range.start);
 
-        assert_eq!(result, 0);
+        assert_eq!(ranges, vec![40..50, 50..52, 52..62, 99..100, 100..101]);
+    }
+
+    proptest! {
+        #[test]
+        fn ranges_match_every_seed(almanac in almanac()) {
+            let almanac = parse_input(&almanac);
+
+            prop_assert_eq!(almanac.lowest_location(), lowest_by_trying(&almanac));
+        }
     }
 }
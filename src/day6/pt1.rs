@@ -11,7 +11,7 @@ use std::collections::BTreeSet;
 use std::iter;
 
 #[derive(Debug)]
-struct Race {
+pub struct Race {
     record: u32,
     time: u32,
 }
@@ -38,9 +38,13 @@ fn parse(input: &str) -> IResult<&str, Vec<Race>> {
     Ok((input, vec))
 }
 
-fn process(input: &str) -> u32 {
-    let (input, races) = parse(input).expect("to be parsed");
+pub fn parse_input(input: &str) -> Vec<Race> {
+    let (_, races) = parse(input).expect("to be parsed");
 
+    races
+}
+
+pub fn solve(races: &[Race]) -> u32 {
     let mut results: Vec<u32> = Vec::new();
     for race in races {
         let mut possibilities = 0;
@@ -57,9 +61,35 @@ fn process(input: &str) -> u32 {
     results.iter().product()
 }
 
+pub fn process(input: &str) -> u32 {
+    solve(&parse_input(input))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::day6::pt2::ways_to_win;
+    use proptest::prelude::*;
+
+    /// A race sheet of one to four races.
+    fn sheet() -> impl Strategy<Value = Vec<(u32, u32)>> {
+        prop::collection::vec((0..200_u32, 0..10_000_u32), 1..=4)
+    }
+
+    fn render(races: &[(u32, u32)]) -> String {
+        let column = |value: fn(&(u32, u32)) -> u32| {
+            races
+                .iter()
+                .map(|race| format!("{:>4}", value(race)))
+                .join("  ")
+        };
+
+        format!(
+            "Time:     {}\nDistance: {}",
+            column(|race| race.0),
+            column(|race| race.1)
+        )
+    }
 
     #[test]
     fn base_example() {
@@ -74,12 +104,16 @@ Distance:  9  40  200
         assert_eq!(result, 288);
     }
 
-    #[test]
-    fn real_input() {
-        let input = include_str!("./input.txt");
-
-        let result = process(input);
+    proptest! {
+        /// The loop over every hold time against the closed form.
+        #[test]
+        fn loop_matches_closed_form(races in sheet()) {
+            let expected: u64 = races
+                .iter()
+                .map(|&(time, record)| ways_to_win(time.into(), record.into()))
+                .product();
 
-        assert_eq!(result, 74698);
+            prop_assert_eq!(u64::from(process(&render(&races))), expected);
+        }
     }
 }
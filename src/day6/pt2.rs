@@ -11,7 +11,7 @@ use std::collections::BTreeSet;
 use std::iter;
 
 #[derive(Debug)]
-struct Race {
+pub struct Race {
     record: u64,
     time: u64,
 }
@@ -35,24 +35,75 @@ fn parse(input: &str) -> IResult<&str, Race> {
     Ok((input, Race { time, record }))
 }
 
-fn process(input: &str) -> u64 {
-    let (input, race) = parse(input).expect("to be parsed");
+pub fn parse_input(input: &str) -> Race {
+    let (_, race) = parse(input).expect("to be parsed");
 
-    let mut possibilities = 0;
+    race
+}
 
-    for i in 0..race.time {
-        let distance = i * (race.time - i);
-        if distance > race.record {
-            possibilities += 1;
-        }
+/// How many ways of holding the button for a whole number of milliseconds
+/// beat `record` in a race of `time`. Holding for `h` goes `h * (time - h)`,
+/// which is above the record between the roots of `h² - time·h + record`;
+/// the float estimate of the lower root is nudged onto the exact integer.
+pub fn ways_to_win(time: u64, record: u64) -> u64 {
+    let beats = |hold: u64| u128::from(hold) * u128::from(time - hold) > u128::from(record);
+
+    // At zero or below the parabola never gets above the record.
+    let discriminant = i128::from(time).pow(2) - 4 * i128::from(record);
+    if discriminant <= 0 {
+        return 0;
+    }
+
+    let root = (discriminant as f64).sqrt();
+    let mut lowest = (((time as f64 - root) / 2.0).max(0.0) as u64).min(time / 2);
+    while lowest > 0 && beats(lowest - 1) {
+        lowest -= 1;
     }
+    while lowest <= time / 2 && !beats(lowest) {
+        lowest += 1;
+    }
+
+    // Holding for `h` and for `time - h` go equally far.
+    if lowest > time / 2 {
+        0
+    } else {
+        time - 2 * lowest + 1
+    }
+}
+
+pub fn solve(race: &Race) -> u64 {
+    ways_to_win(race.time, race.record)
+}
 
-    possibilities
+pub fn process(input: &str) -> u64 {
+    solve(&parse_input(input))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
+
+    /// The straightforward count, trying every hold time.
+    fn ways_by_trying(time: u64, record: u64) -> u64 {
+        (0..=time)
+            .filter(|hold| hold * (time - hold) > record)
+            .count() as u64
+    }
+
+    /// A race sheet of one or two races, whose digits join into one race
+    /// small enough to try every hold time of.
+    fn sheet() -> impl Strategy<Value = String> {
+        prop::collection::vec((0..30_u64, 0..300_u64), 1..=2).prop_map(|races| {
+            let times = races.iter().map(|(time, _)| time.to_string());
+            let records = races.iter().map(|(_, record)| record.to_string());
+            format!(
+                "Time:      {}\nDistance:  {}",
+                times.collect::<Vec<_>>().join("  "),
+                records.collect::<Vec<_>>().join("  ")
+            )
+        })
+    }
 
     #[test]
     fn base_example() {
@@ -68,11 +119,24 @@ Distance:  9  40  200
     }
 
     #[test]
-    fn real_input() {
-        let input = include_str!("./input.txt");
+    fn example_races() {
+        assert_eq!(ways_to_win(7, 9), 4);
+        assert_eq!(ways_to_win(15, 40), 8);
+        assert_eq!(ways_to_win(30, 200), 9);
+        assert_eq!(ways_to_win(4, 4), 0);
+    }
 
-        let result = process(input);
+    proptest! {
+        #[test]
+        fn ways_to_win_matches_trying(time in 0..3_000_u64, record in 0..2_500_000_u64) {
+            prop_assert_eq!(ways_to_win(time, record), ways_by_trying(time, record));
+        }
 
-        assert_eq!(result, 27563421);
+        #[test]
+        fn sheet_matches_trying(sheet in sheet()) {
+            let race = parse_input(&sheet);
+
+            prop_assert_eq!(process(&sheet), ways_by_trying(race.time, race.record));
+        }
     }
 }
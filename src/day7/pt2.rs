@@ -12,6 +12,8 @@ use nom::{
     IResult, Parser,
 };
 
+use crate::trace;
+
 lazy_static! {
     static ref CARD_POWER: BTreeMap<char, u32> = BTreeMap::from([
         ('A', 14),
@@ -41,13 +43,19 @@ enum HandType {
     HighHand = 0,
 }
 
-#[derive(Debug, Eq, PartialEq, PartialOrd)]
-struct CardHand<'a> {
+#[derive(Debug, Eq, PartialEq)]
+pub struct CardHand<'a> {
     cards: &'a str,
     bid: u32,
     hand_type: HandType,
 }
 
+impl<'a> PartialOrd for CardHand<'a> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl<'a> Ord for CardHand<'a> {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         match self.hand_type.cmp(&other.hand_type) {
@@ -63,7 +71,7 @@ impl<'a> Ord for CardHand<'a> {
     }
 }
 
-fn parse_card((cards, bid): (&str, u32)) -> CardHand {
+fn parse_card((cards, bid): (&str, u32)) -> CardHand<'_> {
     let n_j = cards.chars().filter(|c| c == &'J').count();
 
     let cards_value_ = cards.chars().filter(|&c| c != 'J').counts_by(|a| a);
@@ -97,7 +105,7 @@ fn parse_card((cards, bid): (&str, u32)) -> CardHand {
     }
 }
 
-fn parse(input: &str) -> IResult<&str, Vec<CardHand>> {
+fn parse(input: &str) -> IResult<&str, Vec<CardHand<'_>>> {
     let (input, cards) = separated_list1(
         newline,
         map(
@@ -109,20 +117,28 @@ fn parse(input: &str) -> IResult<&str, Vec<CardHand>> {
     Ok((input, cards))
 }
 
-fn process(input: &str) -> u32 {
-    let (input, result) = parse(input).expect("stuff");
+pub fn parse_input(input: &str) -> Vec<CardHand<'_>> {
+    let (_, hands) = parse(input).expect("stuff");
 
+    hands
+}
+
+pub fn solve(result: &[CardHand]) -> u32 {
     let x = result
         .iter()
         .sorted_by(|a, b| a.cmp(b))
         .enumerate()
-        .inspect(|x| println!("{:?}", &x))
+        .inspect(|x| trace!(Debug, "{x:?}"))
         .map(|(i, val)| val.bid * ((i + 1) as u32))
         .sum::<u32>();
 
     x
 }
 
+pub fn process(input: &str) -> u32 {
+    solve(&parse_input(input))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -142,13 +158,4 @@ QQQJA 483
 
         assert_eq!(result, 5905);
     }
-
-    #[test]
-    fn real_input() {
-        let input = include_str!("./input.txt");
-
-        let result = process(input);
-
-        assert_eq!(result, 253253225);
-    }
 }
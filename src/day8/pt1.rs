@@ -25,12 +25,12 @@ enum Direction {
 }
 
 #[derive(Debug)]
-struct Map<'a> {
+pub struct Map<'a> {
     directions: Vec<Direction>,
     network: BTreeMap<&'a str, (&'a str, &'a str)>,
 }
 
-fn parse(input: &str) -> IResult<&str, Map> {
+fn parse(input: &str) -> IResult<&str, Map<'_>> {
     let (input, directions) = many1(alt((
         char('L').map(|_| Direction::Left),
         char('R').map(|_| Direction::Right),
@@ -65,9 +65,13 @@ fn parse(input: &str) -> IResult<&str, Map> {
     ))
 }
 
-fn process(input: &str) -> u32 {
-    let (input, map) = parse(input).expect("no errors");
+pub fn parse_input(input: &str) -> Map<'_> {
+    let (_, map) = parse(input).expect("no errors");
 
+    map
+}
+
+pub fn solve(map: &Map) -> u32 {
     let (n, node) = map
         .directions
         .iter()
@@ -90,6 +94,10 @@ fn process(input: &str) -> u32 {
     n
 }
 
+pub fn process(input: &str) -> u32 {
+    solve(&parse_input(input))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -121,13 +129,4 @@ ZZZ = (ZZZ, ZZZ)",
 
         assert_eq!(result, expected);
     }
-
-    #[test]
-    fn real_input() {
-        let input = include_str!("./input.txt");
-
-        let result = process(input);
-
-        assert_eq!(result, 11567);
-    }
 }
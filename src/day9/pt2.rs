@@ -11,16 +11,14 @@ use nom::{
     IResult,
 };
 
-fn parse(input: &str) -> Vec<Vec<i64>> {
+pub fn parse_input(input: &str) -> Vec<Vec<i64>> {
     input
         .lines()
         .map(|line| line.split(' ').map(|n| n.parse::<i64>().unwrap()).collect())
         .collect::<Vec<Vec<i64>>>()
 }
 
-fn process(input: &str) -> i64 {
-    let vec = parse(input);
-
+pub fn solve(vec: &[Vec<i64>]) -> i64 {
     let items: Vec<i64> = vec
         .iter()
         .map(|new_row| {
@@ -58,6 +56,10 @@ fn process(input: &str) -> i64 {
     items.iter().sum()
 }
 
+pub fn process(input: &str) -> i64 {
+    solve(&parse_input(input))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -75,13 +77,4 @@ mod tests {
 
         assert_eq!(result, expected);
     }
-
-    #[test]
-    fn real_input() {
-        let input = include_str!("./input.txt");
-
-        let result = process(input);
-
-        assert_eq!(result, 973);
-    }
 }
@@ -0,0 +1,516 @@
+use core::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::workspace::Workspace;
+
+/// What a day's README says about the puzzle: its title and, for each part
+/// that is unlocked, the example it walks through and the answer it states.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Puzzle {
+    pub day: u8,
+    pub title: String,
+    pub parts: Vec<PartText>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct PartText {
+    pub example: Option<String>,
+    pub answer: Option<String>,
+    /// The answer recorded after solving, from "Your puzzle answer was ...".
+    pub puzzle_answer: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ReadmeError {
+    MissingHeader,
+}
+
+impl fmt::Display for ReadmeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadmeError::MissingHeader => write!(f, "no `--- Day N: Title ---` header"),
+        }
+    }
+}
+
+impl std::error::Error for ReadmeError {}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Item<'a> {
+    Prose(&'a str),
+    Block(Vec<&'a str>),
+}
+
+/// A line without the emphasis or code markup it may end in.
+fn unmarked(line: &str) -> &str {
+    line.trim_end_matches(['*', '`'])
+}
+
+/// Sentences start with a capital, end in punctuation and have more than one
+/// word; example data rarely does all three ("seed-to-soil map:" does not).
+fn is_prose(line: &str) -> bool {
+    !line.starts_with(|c: char| c.is_lowercase())
+        && line.split_whitespace().count() >= 2
+        && unmarked(line).ends_with(['.', '?', '!', ':'])
+}
+
+/// Whether a sentence hands over a new example input, as opposed to a worked
+/// step ("here is what the third example history looks like:") or a table of
+/// intermediate results ("In this example, the number of arrangements is:").
+fn introduces_example(line: &str) -> bool {
+    let line = line.to_lowercase();
+
+    line.contains("for example") || line.ends_with("example:") || line.contains("another example")
+}
+
+/// Splits a part into prose lines and the blocks of data between them. Blocks
+/// are either fenced with ``` (as the downloader writes them) or runs of
+/// non-prose lines, which may contain single blank lines (several patterns in
+/// one example).
+fn items(text: &str) -> Vec<Item<'_>> {
+    let mut items = Vec::new();
+    let mut lines = text.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        if line.starts_with("```") {
+            let block = lines
+                .by_ref()
+                .take_while(|l| !l.starts_with("```"))
+                .collect();
+            items.push(Item::Block(block));
+        } else if line.trim().is_empty() {
+            continue;
+        } else if is_prose(line) {
+            items.push(Item::Prose(line));
+        } else {
+            let mut block = vec![line];
+            while let Some(next) = lines.peek() {
+                if next.trim().is_empty() {
+                    let mut ahead = lines.clone();
+                    ahead.next();
+                    match ahead.peek() {
+                        Some(after) if !after.trim().is_empty() && !is_prose(after) => {
+                            block.push("");
+                            lines.next();
+                        }
+                        _ => break,
+                    }
+                } else if is_prose(next) || next.starts_with("```") {
+                    break;
+                } else {
+                    block.push(lines.next().unwrap());
+                }
+            }
+            items.push(Item::Block(block));
+        }
+    }
+
+    items
+}
+
+/// Last number in a sentence, preferring one emphasised with `**`.
+fn stated_number(line: &str) -> Option<String> {
+    let emphasised = line
+        .split("**")
+        .skip(1)
+        .step_by(2)
+        .filter(|x| !x.is_empty() && x.chars().all(|c| c.is_ascii_digit() || c == '-'))
+        .last();
+
+    emphasised.map(str::to_string).or_else(|| {
+        line.split(|c: char| !c.is_ascii_digit() && c != '-')
+            .filter(|x| x.chars().any(|c| c.is_ascii_digit()))
+            .map(|x| x.trim_matches('-'))
+            .next_back()
+            .map(str::to_string)
+    })
+}
+
+fn parse_part(text: &str, fallback_example: Option<&str>) -> PartText {
+    let puzzle_answer = text.lines().find_map(|line| {
+        let answer = line.trim().strip_prefix("Your puzzle answer was ")?;
+        Some(
+            answer
+                .trim_end_matches('.')
+                .trim_matches(['*', '`'])
+                .to_string(),
+        )
+    });
+
+    let items = items(text);
+    let question = items
+        .iter()
+        .rposition(|item| matches!(item, Item::Prose(line) if unmarked(line).ends_with('?')))
+        .unwrap_or(items.len());
+
+    let answer_at = items[..question].iter().rposition(
+        |item| matches!(item, Item::Prose(line) if line.chars().any(|c| c.is_ascii_digit())),
+    );
+
+    let introduced_example = |index: usize| match (&items[index], index.checked_sub(1)) {
+        (Item::Block(block), Some(before)) => match items[before] {
+            Item::Prose(line) if introduces_example(line) => Some(block.join("\n")),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    // "For example, here is a situation that requires 6 steps:" states the
+    // answer and introduces its example in the same sentence.
+    let stated_with_answer =
+        answer_at.and_then(|index| match (&items[index], items.get(index + 1)) {
+            (Item::Prose(line), Some(Item::Block(block))) if line.ends_with(':') => {
+                Some(block.join("\n"))
+            }
+            _ => None,
+        });
+
+    // Part one opens with the puzzle's example and later blocks walk through
+    // it, while part two tends to add examples and answer the last one.
+    let mut introduced = (0..answer_at.unwrap_or(question)).filter_map(introduced_example);
+    let introduced = match fallback_example {
+        None => introduced.next(),
+        Some(_) => introduced.next_back(),
+    };
+
+    let example = stated_with_answer
+        .or(introduced)
+        .or_else(|| fallback_example.map(str::to_string));
+
+    PartText {
+        example,
+        answer: answer_at.and_then(|index| match items[index] {
+            Item::Prose(line) => stated_number(line),
+            Item::Block(_) => None,
+        }),
+        puzzle_answer,
+    }
+}
+
+/// Copying the puzzle text by hand sometimes adds a dash to the rule.
+fn parse_header(line: &str) -> Option<(u8, String)> {
+    let header = line
+        .trim()
+        .trim_start_matches('-')
+        .strip_prefix(" Day ")?
+        .strip_suffix(" ---")?;
+    let (day, title) = header.split_once(": ")?;
+
+    Some((day.parse().ok()?, title.to_string()))
+}
+
+pub fn parse_readme(text: &str) -> Result<Puzzle, ReadmeError> {
+    let (header_at, (day, title)) = text
+        .lines()
+        .enumerate()
+        .find_map(|(index, line)| Some((index, parse_header(line)?)))
+        .ok_or(ReadmeError::MissingHeader)?;
+
+    let body = text
+        .lines()
+        .skip(header_at + 1)
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut parts = Vec::new();
+    for part_text in body.split("--- Part Two ---") {
+        let first_example = parts.first().and_then(|p: &PartText| p.example.clone());
+        parts.push(parse_part(part_text, first_example.as_deref()));
+    }
+
+    Ok(Puzzle { day, title, parts })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Example {
+    pub part: u8,
+    pub input: String,
+    pub answer: String,
+    /// Hand-written examples survive `aoc examples` regenerating the file.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub manual: bool,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Examples {
+    /// Parts that are not solved yet; the harness skips their examples.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pending: Vec<u8>,
+    #[serde(default, rename = "example")]
+    pub examples: Vec<Example>,
+}
+
+#[derive(Debug)]
+pub enum ExamplesError {
+    Readme(ReadmeError),
+    Io(PathBuf, io::Error),
+    Toml(PathBuf, toml::de::Error),
+}
+
+impl fmt::Display for ExamplesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExamplesError::Readme(error) => write!(f, "README: {error}"),
+            ExamplesError::Io(path, error) => write!(f, "{}: {error}", path.display()),
+            ExamplesError::Toml(path, error) => write!(f, "{}: {error}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for ExamplesError {}
+
+impl From<&Puzzle> for Examples {
+    fn from(puzzle: &Puzzle) -> Self {
+        let examples = puzzle
+            .parts
+            .iter()
+            .zip(1..)
+            .filter_map(|(part, number)| {
+                Some(Example {
+                    part: number,
+                    input: part.example.clone()?,
+                    answer: part.answer.clone()?,
+                    manual: false,
+                })
+            })
+            .collect();
+
+        Examples {
+            pending: Vec::new(),
+            examples,
+        }
+    }
+}
+
+impl Examples {
+    pub fn load(workspace: &Workspace, day: u8) -> Result<Examples, ExamplesError> {
+        let path = workspace.examples_path(day);
+
+        match fs::read_to_string(&path) {
+            Ok(text) => toml::from_str(&text).map_err(|error| ExamplesError::Toml(path, error)),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Examples::default()),
+            Err(error) => Err(ExamplesError::Io(path, error)),
+        }
+    }
+
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).expect("examples should serialize")
+    }
+
+    /// Replaces the extracted examples with `extracted`, keeping manual ones.
+    /// A part with a manual example takes nothing from the README, so a bad
+    /// extraction is fixed by writing the example by hand.
+    pub fn update(&mut self, extracted: Examples) {
+        self.examples.retain(|example| example.manual);
+        let manual_parts: Vec<u8> = self.examples.iter().map(|example| example.part).collect();
+
+        self.examples.extend(
+            extracted
+                .examples
+                .into_iter()
+                .filter(|example| !manual_parts.contains(&example.part)),
+        );
+        self.examples.sort_by_key(|example| example.part);
+    }
+}
+
+pub fn read_readme(workspace: &Workspace, day: u8) -> Result<Puzzle, ExamplesError> {
+    let path = workspace.readme_path(day);
+    let text = fs::read_to_string(&path).map_err(|error| ExamplesError::Io(path, error))?;
+
+    parse_readme(&text).map_err(ExamplesError::Readme)
+}
+
+/// Re-extracts the examples of `day` from its README into its examples file.
+pub fn update_examples(workspace: &Workspace, day: u8) -> Result<Examples, ExamplesError> {
+    let puzzle = read_readme(workspace, day)?;
+
+    let mut examples = Examples::load(workspace, day)?;
+    examples.update(Examples::from(&puzzle));
+
+    let path = workspace.examples_path(day);
+    fs::write(&path, examples.to_toml()).map_err(|error| ExamplesError::Io(path, error))?;
+
+    Ok(examples)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::{self, SOLUTIONS};
+    use itertools::Itertools;
+    use rstest::*;
+
+    const README: &str = "Our sponsors help make Advent of Code possible:
+--- Day 9: Mirage Maintenance ---
+Each line in the report contains the history of a single value. For example:
+
+0 3 6 9 12 15
+1 3 6 10 15 21
+To best protect the oasis, start by making a new sequence from the difference at each step.
+
+0   3   6   9  12  15
+  3   3   3   3   3
+If you find the next value for each history in this example and add them together, you get 114.
+
+Analyze your OASIS report. What is the sum of these extrapolated values?
+
+Your puzzle answer was 1479011877.
+
+--- Part Two ---
+Surely it's safe to just extrapolate backwards as well, right?
+
+Adding all three new values together produces 2.
+
+What is the sum of these extrapolated values?
+";
+
+    #[test]
+    fn parse_plain_readme() {
+        let puzzle = parse_readme(README).unwrap();
+
+        assert_eq!(puzzle.day, 9);
+        assert_eq!(puzzle.title, "Mirage Maintenance");
+        assert_eq!(
+            puzzle.parts,
+            vec![
+                PartText {
+                    example: Some("0 3 6 9 12 15\n1 3 6 10 15 21".to_string()),
+                    answer: Some("114".to_string()),
+                    puzzle_answer: Some("1479011877".to_string()),
+                },
+                PartText {
+                    example: Some("0 3 6 9 12 15\n1 3 6 10 15 21".to_string()),
+                    answer: Some("2".to_string()),
+                    puzzle_answer: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_markdown_readme() {
+        let readme = "--- Day 1: Trebuchet?! ---
+For example:
+
+```
+1abc2
+treb7uchet
+```
+
+In this example, the calibration values are **12** and **77**. Adding these together produces **89**.
+
+What is the sum of all of the calibration values?
+";
+
+        let puzzle = parse_readme(readme).unwrap();
+
+        assert_eq!(puzzle.title, "Trebuchet?!");
+        assert_eq!(
+            puzzle.parts[0].example.as_deref(),
+            Some("1abc2\ntreb7uchet")
+        );
+        assert_eq!(puzzle.parts[0].answer.as_deref(), Some("89"));
+    }
+
+    #[test]
+    fn blocks_keep_single_blank_lines() {
+        let text = "For example:\n\n#.#\n.#.\n\n##.\n..#\nThis gives a total of 405.\n";
+
+        assert_eq!(
+            items(text),
+            vec![
+                Item::Prose("For example:"),
+                Item::Block(vec!["#.#", ".#.", "", "##.", "..#"]),
+                Item::Prose("This gives a total of 405."),
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case("you get 114.", Some("114"))]
+    #[case("previous values of -3 and 0 produces 2.", Some("2"))]
+    #[case("a total of **405**, not 12.", Some("405"))]
+    #[case("the first value is **-3**.", Some("-3"))]
+    #[case("no numbers here.", None)]
+    fn stated_numbers(#[case] line: &str, #[case] expected: Option<&str>) {
+        assert_eq!(stated_number(line).as_deref(), expected);
+    }
+
+    #[test]
+    fn missing_header() {
+        assert_eq!(parse_readme(""), Err(ReadmeError::MissingHeader));
+    }
+
+    #[test]
+    fn update_keeps_manual_examples() {
+        let manual = Example {
+            part: 2,
+            input: "x".to_string(),
+            answer: "1".to_string(),
+            manual: true,
+        };
+        let extracted = |answer: &str| Example {
+            part: 1,
+            input: "y".to_string(),
+            answer: answer.to_string(),
+            manual: false,
+        };
+        let mut examples = Examples {
+            pending: vec![2],
+            examples: vec![manual.clone(), extracted("old")],
+        };
+
+        examples.update(Examples {
+            pending: Vec::new(),
+            examples: vec![
+                extracted("new"),
+                Example {
+                    part: 2,
+                    ..extracted("ignored")
+                },
+            ],
+        });
+
+        assert_eq!(examples.examples, vec![extracted("new"), manual]);
+        assert_eq!(
+            toml::from_str::<Examples>(&examples.to_toml()),
+            Ok(examples)
+        );
+    }
+
+    /// Runs every registered solution against the examples of its day.
+    #[test]
+    fn registered_examples() {
+        let workspace = Workspace::from_env();
+
+        let failures: Vec<String> = SOLUTIONS
+            .iter()
+            .map(|solution| solution.day)
+            .unique()
+            .flat_map(|day| {
+                let Examples { pending, examples } = Examples::load(&workspace, day).unwrap();
+                examples
+                    .into_iter()
+                    .filter(move |example| !pending.contains(&example.part))
+                    .map(move |example| (day, example))
+            })
+            .filter_map(|(day, example)| {
+                let solution = registry::find(day, example.part)?;
+                let result = (solution.run)(&example.input);
+
+                (result != example.answer).then(|| {
+                    format!(
+                        "day {day} part {}: expected {}, got {result}",
+                        example.part, example.answer
+                    )
+                })
+            })
+            .collect();
+
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+}
@@ -0,0 +1,313 @@
+//! Puzzle pages as README text: the `<article>`s of a day's page, and the
+//! answers given so far, as Markdown the example extractor can read.
+//!
+//! Paragraphs become lines of their own, `<pre>` blocks are fenced with
+//! ```, `<em>` (which the puzzles use for answers) becomes `**` and inline
+//! `<code>` becomes backticks. Everything else keeps only its text.
+
+#[derive(Debug, PartialEq, Eq)]
+enum Token<'a> {
+    Open(&'a str),
+    Close(&'a str),
+    Text(&'a str),
+}
+
+/// Tags and the text between them. Comments, doctypes and the like are
+/// skipped, and attributes are dropped.
+fn tokens(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            tokens.push(Token::Text(rest));
+            break;
+        };
+        if start > 0 {
+            tokens.push(Token::Text(&rest[..start]));
+        }
+
+        let Some(end) = rest[start..].find('>') else {
+            break;
+        };
+        let tag = &rest[start + 1..start + end];
+        rest = &rest[start + end + 1..];
+
+        let name_end = tag
+            .find(|c: char| c.is_whitespace() || c == '/')
+            .unwrap_or(tag.len());
+        match tag.strip_prefix('/') {
+            Some(name) => tokens.push(Token::Close(name.trim())),
+            None if tag.starts_with(['!', '?']) => {}
+            None => tokens.push(Token::Open(&tag[..name_end])),
+        }
+    }
+
+    tokens
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let entity = rest[1..].find(';').map(|end| &rest[1..end + 1]);
+        let c = entity.and_then(|entity| match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => {
+                let code = match entity.strip_prefix("#x") {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                    None => entity.strip_prefix('#')?.parse().ok()?,
+                };
+                char::from_u32(code)
+            }
+        });
+
+        match (c, entity) {
+            (Some(c), Some(entity)) => {
+                decoded.push(c);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    decoded.push_str(rest);
+    decoded
+}
+
+#[derive(Default)]
+struct Converter {
+    blocks: Vec<String>,
+    /// Text of the paragraph, heading or list item being read.
+    inline: String,
+    list: Vec<String>,
+    /// Where the backtick of the inline `<code>` being read is.
+    code_at: Option<usize>,
+    pre: Option<String>,
+    articles: usize,
+}
+
+impl Converter {
+    fn take_inline(&mut self) -> String {
+        let text = std::mem::take(&mut self.inline);
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    fn open(&mut self, name: &str) {
+        match (name, &mut self.pre) {
+            (_, Some(_)) => {}
+            ("article", _) => self.articles += 1,
+            ("p" | "h2" | "li", _) => {
+                self.inline.clear();
+            }
+            ("pre", _) => self.pre = Some(String::new()),
+            ("em", _) => self.inline.push_str("**"),
+            ("code", _) => {
+                self.code_at = Some(self.inline.len());
+                self.inline.push('`');
+            }
+            _ => {}
+        }
+    }
+
+    fn close(&mut self, name: &str) {
+        match name {
+            "pre" => {
+                if let Some(pre) = self.pre.take() {
+                    self.blocks
+                        .push(format!("```\n{}\n```", pre.trim_end_matches('\n')));
+                }
+            }
+            _ if self.pre.is_some() => {}
+            "article" => self.articles = self.articles.saturating_sub(1),
+            "h2" | "p" => {
+                let text = self.take_inline();
+                // Outside the articles only the answers given so far matter.
+                if !text.is_empty()
+                    && (self.articles > 0 || text.starts_with("Your puzzle answer was"))
+                {
+                    self.blocks.push(text);
+                }
+            }
+            "li" => {
+                let text = self.take_inline();
+                self.list.push(format!("- {text}"));
+            }
+            "ul" | "ol" if self.articles > 0 && !self.list.is_empty() => {
+                let list = std::mem::take(&mut self.list);
+                self.blocks.push(list.join("\n"));
+            }
+            "ul" | "ol" => self.list.clear(),
+            "em" => self.inline.push_str("**"),
+            "code" => match self.code_at.take() {
+                // An answer is emphasised code; the emphasis says enough.
+                Some(at)
+                    if self.inline[at + 1..].starts_with("**")
+                        && self.inline.ends_with("**")
+                        && self.inline.len() > at + 4 =>
+                {
+                    self.inline.remove(at);
+                }
+                _ => self.inline.push('`'),
+            },
+            _ => {}
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        let text = decode_entities(text);
+        match &mut self.pre {
+            Some(pre) => pre.push_str(&text),
+            None => self.inline.push_str(&text),
+        }
+    }
+}
+
+/// The puzzle text of a day's page, empty if it has no `<article>`.
+pub fn to_markdown(html: &str) -> String {
+    let mut converter = Converter::default();
+
+    for token in tokens(html) {
+        match token {
+            Token::Open(name) => converter.open(&name.to_ascii_lowercase()),
+            Token::Close(name) => converter.close(&name.to_ascii_lowercase()),
+            Token::Text(text) => converter.text(text),
+        }
+    }
+
+    let blocks = converter.blocks;
+    if !blocks.iter().any(|block| block.starts_with("--- Day ")) {
+        return String::new();
+    }
+
+    blocks.join("\n\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::examples::parse_readme;
+    use rstest::*;
+
+    const PAGE: &str = r#"<!DOCTYPE html>
+<html lang="en-us">
+<head><title>Day 6 - Advent of Code 2023</title></head>
+<body>
+<header><h1 class="title-global"><a href="/">Advent of Code</a></h1></header>
+<main>
+<article class="day-desc"><h2>--- Day 6: Wait For It ---</h2><p>The ferry quickly brings you across Island Island.</p>
+<p>For example:</p>
+<pre><code>Time:      7  15   30
+Distance:  9  40  200
+</code></pre>
+<p>This document describes three races:</p>
+<ul>
+<li>The first race lasts <code>7</code> milliseconds &amp; the record is <code>9</code>.</li>
+<li>The second race lasts 15 milliseconds.</li>
+</ul>
+<p>In this example, if you multiply these values together, you get <code><em>288</em></code> (<code>4</code> * <code>8</code> * <code>9</code>).</p>
+<p>Determine the number of ways you could beat the record in each race; <em>what do you get if you multiply these numbers together?</em></p>
+</article>
+<p>Your puzzle answer was <code>1731600</code>.</p><article class="day-desc"><h2 id="part2">--- Part Two ---</h2><p>As the race is about to start, you realize the piece of paper has <span title="Keming!">very bad</span> kerning.</p>
+<p>Now, you have to figure out how many ways there are to win this single race. In this example, the race lasts for <code>71530</code> milliseconds and the record distance you need to beat is <code>940200</code> millimeters. You could hold the button anywhere from <code>14</code> to <code>71516</code> milliseconds and beat the record, a total of <code><em>71503</em></code> ways!</p>
+<p><em>How many ways can you beat the record in this one much longer race?</em></p>
+</article>
+<p>Your puzzle answer was <code>40087680</code>.</p><p class="day-success">Both parts of this puzzle are complete! They provide two gold stars: **</p>
+<p>At this point, you should <a href="/2023">return to your Advent calendar</a> and try another puzzle.</p>
+</main>
+</body>
+</html>
+"#;
+
+    const MARKDOWN: &str = "--- Day 6: Wait For It ---
+
+The ferry quickly brings you across Island Island.
+
+For example:
+
+```
+Time:      7  15   30
+Distance:  9  40  200
+```
+
+This document describes three races:
+
+- The first race lasts `7` milliseconds & the record is `9`.
+- The second race lasts 15 milliseconds.
+
+In this example, if you multiply these values together, you get **288** (`4` * `8` * `9`).
+
+Determine the number of ways you could beat the record in each race; **what do you get if you multiply these numbers together?**
+
+Your puzzle answer was `1731600`.
+
+--- Part Two ---
+
+As the race is about to start, you realize the piece of paper has very bad kerning.
+
+Now, you have to figure out how many ways there are to win this single race. In this example, the race lasts for `71530` milliseconds and the record distance you need to beat is `940200` millimeters. You could hold the button anywhere from `14` to `71516` milliseconds and beat the record, a total of **71503** ways!
+
+**How many ways can you beat the record in this one much longer race?**
+
+Your puzzle answer was `40087680`.
+";
+
+    #[test]
+    fn page_to_markdown() {
+        assert_eq!(to_markdown(PAGE), MARKDOWN);
+    }
+
+    #[test]
+    fn markdown_has_examples() {
+        let puzzle = parse_readme(&to_markdown(PAGE)).unwrap();
+
+        let parts: Vec<_> = puzzle
+            .parts
+            .iter()
+            .map(|part| {
+                (
+                    part.example.as_deref(),
+                    part.answer.as_deref(),
+                    part.puzzle_answer.as_deref(),
+                )
+            })
+            .collect();
+        let example = "Time:      7  15   30\nDistance:  9  40  200";
+        assert_eq!(puzzle.title, "Wait For It");
+        assert_eq!(
+            parts,
+            vec![
+                (Some(example), Some("288"), Some("1731600")),
+                (Some(example), Some("71503"), Some("40087680")),
+            ]
+        );
+    }
+
+    #[test]
+    fn not_a_puzzle() {
+        assert_eq!(
+            to_markdown("<html><body><p>404 Not Found</p></body></html>"),
+            ""
+        );
+    }
+
+    #[rstest]
+    #[case("a &lt;b&gt; &amp;&amp; c", "a <b> && c")]
+    #[case("it&#39;s &#x27;fine&#x27;", "it's 'fine'")]
+    #[case("AT&T; & more", "AT&T; & more")]
+    fn entities(#[case] text: &str, #[case] expected: &str) {
+        assert_eq!(decode_entities(text), expected);
+    }
+}
@@ -0,0 +1,540 @@
+//! Just enough HTTP to talk to the puzzle server, behind a trait so the
+//! transport can be swapped: plain HTTP over a `TcpStream` for a local
+//! stand-in server, and the `curl` command for the real, HTTPS-only site.
+//!
+//! Which one is used follows the URL unless `http` in `aoc.toml` (or
+//! `AOC_HTTP`) names a [`Backend`]. There is no TLS in this crate, so talking
+//! to `https://` needs `curl` on the `PATH`.
+
+use core::fmt;
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    Get,
+    Post,
+}
+
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Method::Get => write!(f, "GET"),
+            Method::Post => write!(f, "POST"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub method: Method,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    /// Sent as `application/x-www-form-urlencoded`.
+    pub form: Option<String>,
+}
+
+impl Request {
+    pub fn get(url: impl Into<String>) -> Self {
+        Request {
+            method: Method::Get,
+            url: url.into(),
+            headers: Vec::new(),
+            form: None,
+        }
+    }
+
+    pub fn post(url: impl Into<String>, form: impl Into<String>) -> Self {
+        Request {
+            method: Method::Post,
+            url: url.into(),
+            headers: Vec::new(),
+            form: Some(form.into()),
+        }
+    }
+
+    pub fn header(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.headers.push((name.to_string(), value.into()));
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+#[derive(Debug)]
+pub enum HttpError {
+    /// The backend cannot handle this URL, e.g. HTTPS over plain TCP.
+    UnsupportedUrl(String),
+    /// The command a backend runs is not installed.
+    MissingCommand {
+        program: String,
+        reason: Reason,
+    },
+    Io(io::Error),
+    Malformed(String),
+}
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HttpError::UnsupportedUrl(url) => write!(f, "cannot request {url}"),
+            HttpError::MissingCommand {
+                program,
+                reason: Reason::Configured,
+            } => write!(
+                f,
+                "`{program}` is the configured HTTP backend but was not found, install it \
+                 or change `http` in aoc.toml or AOC_HTTP"
+            ),
+            HttpError::MissingCommand {
+                program,
+                reason: Reason::Url,
+            } => write!(
+                f,
+                "`{program}` is needed for https:// URLs but was not found, install it \
+                 or point AOC_BASE_URL at an http:// server"
+            ),
+            HttpError::Io(error) => write!(f, "{error}"),
+            HttpError::Malformed(reason) => write!(f, "malformed response: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for HttpError {}
+
+impl From<io::Error> for HttpError {
+    fn from(error: io::Error) -> Self {
+        HttpError::Io(error)
+    }
+}
+
+pub trait Http {
+    fn send(&self, request: &Request) -> Result<Response, HttpError>;
+}
+
+/// Why a [`Backend`] is the one in use, so an error can say what to change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reason {
+    /// Named by `http` in `aoc.toml` or by `AOC_HTTP`.
+    Configured,
+    /// Picked by [`Backend::for_url`].
+    Url,
+}
+
+/// The [`Http`] implementations a [`Client`](crate::client::Client) can be
+/// configured with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    /// [`PlainHttp`], `http://` only.
+    Plain,
+    /// [`Curl`], which needs the `curl` command.
+    Curl,
+}
+
+impl Backend {
+    /// Plain TCP for `http://`, `curl` for anything else.
+    pub fn for_url(url: &str) -> Backend {
+        if url.starts_with("http://") {
+            Backend::Plain
+        } else {
+            Backend::Curl
+        }
+    }
+
+    pub fn build(self, reason: Reason) -> Box<dyn Http> {
+        match self {
+            Backend::Plain => Box::new(PlainHttp::default()),
+            Backend::Curl => Box::new(Curl {
+                reason,
+                ..Curl::default()
+            }),
+        }
+    }
+}
+
+impl FromStr for Backend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "plain" => Ok(Backend::Plain),
+            "curl" => Ok(Backend::Curl),
+            _ => Err(format!(
+                "unknown HTTP backend {s:?}, expected plain or curl"
+            )),
+        }
+    }
+}
+
+/// HTTP/1.1 over a `TcpStream`, one connection per request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlainHttp {
+    pub timeout: Duration,
+}
+
+impl Default for PlainHttp {
+    fn default() -> Self {
+        PlainHttp {
+            timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+/// `http://host[:port]/path` as its host, port and path.
+fn split_url(url: &str) -> Option<(&str, u16, &str)> {
+    let rest = url.strip_prefix("http://")?;
+    let (authority, path) = match rest.find('/') {
+        Some(index) => rest.split_at(index),
+        None => (rest, "/"),
+    };
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) => (host, port.parse().ok()?),
+        None => (authority, 80),
+    };
+
+    (!host.is_empty()).then_some((host, port, path))
+}
+
+fn decode_chunked(mut body: &[u8]) -> Option<Vec<u8>> {
+    let mut decoded = Vec::new();
+
+    loop {
+        let line_end = body.windows(2).position(|x| x == b"\r\n")?;
+        let size = std::str::from_utf8(&body[..line_end]).ok()?;
+        let size = usize::from_str_radix(size.split(';').next()?.trim(), 16).ok()?;
+        body = &body[line_end + 2..];
+
+        if size == 0 {
+            return Some(decoded);
+        }
+
+        decoded.extend_from_slice(body.get(..size)?);
+        body = body.get(size + 2..)?;
+    }
+}
+
+/// Parses a whole response, read until the server closed the connection.
+fn parse_response(raw: &[u8]) -> Result<Response, HttpError> {
+    let malformed = |reason: &str| HttpError::Malformed(reason.to_string());
+
+    let head_end = raw
+        .windows(4)
+        .position(|x| x == b"\r\n\r\n")
+        .ok_or_else(|| malformed("no end of headers"))?;
+    let head = std::str::from_utf8(&raw[..head_end]).map_err(|_| malformed("headers"))?;
+    let body = &raw[head_end + 4..];
+
+    let mut lines = head.split("\r\n");
+    let status = lines
+        .next()
+        .and_then(|line| line.split(' ').nth(1))
+        .and_then(|status| status.parse().ok())
+        .ok_or_else(|| malformed("status line"))?;
+
+    let mut chunked = false;
+    let mut length = None;
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+
+        if name.eq_ignore_ascii_case("transfer-encoding") {
+            chunked = value.eq_ignore_ascii_case("chunked");
+        } else if name.eq_ignore_ascii_case("content-length") {
+            length = value.parse::<usize>().ok();
+        }
+    }
+
+    let body = match (chunked, length) {
+        (true, _) => decode_chunked(body).ok_or_else(|| malformed("chunked body"))?,
+        (false, Some(length)) => body
+            .get(..length)
+            .ok_or_else(|| malformed("body shorter than its length"))?
+            .to_vec(),
+        (false, None) => body.to_vec(),
+    };
+
+    Ok(Response {
+        status,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    })
+}
+
+impl Http for PlainHttp {
+    fn send(&self, request: &Request) -> Result<Response, HttpError> {
+        let (host, port, path) = split_url(&request.url)
+            .ok_or_else(|| HttpError::UnsupportedUrl(request.url.clone()))?;
+
+        let mut message = format!(
+            "{} {path} HTTP/1.1\r\nHost: {host}\r\nConnection: close\r\n",
+            request.method
+        );
+        for (name, value) in &request.headers {
+            message.push_str(&format!("{name}: {value}\r\n"));
+        }
+        if let Some(form) = &request.form {
+            message.push_str("Content-Type: application/x-www-form-urlencoded\r\n");
+            message.push_str(&format!("Content-Length: {}\r\n", form.len()));
+        }
+        message.push_str("\r\n");
+        message.push_str(request.form.as_deref().unwrap_or_default());
+
+        let mut stream = TcpStream::connect((host, port))?;
+        stream.set_read_timeout(Some(self.timeout))?;
+        stream.set_write_timeout(Some(self.timeout))?;
+        stream.write_all(message.as_bytes())?;
+
+        let mut raw = Vec::new();
+        stream.read_to_end(&mut raw)?;
+
+        parse_response(&raw)
+    }
+}
+
+/// The `curl` command. Headers go through its stdin so a session cookie never
+/// shows up in the process list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Curl {
+    pub program: String,
+    /// Named in the error when `program` is missing.
+    pub reason: Reason,
+}
+
+impl Default for Curl {
+    fn default() -> Self {
+        Curl {
+            program: "curl".to_string(),
+            reason: Reason::Url,
+        }
+    }
+}
+
+/// Separates the body from the status code `--write-out` appends to it.
+const STATUS_MARKER: &str = "\n--aoc-status:";
+
+impl Http for Curl {
+    fn send(&self, request: &Request) -> Result<Response, HttpError> {
+        let mut command = Command::new(&self.program);
+        command
+            .args(["--silent", "--show-error", "--header", "@-"])
+            .args(["--request", &request.method.to_string()])
+            .args(["--write-out", &format!("{STATUS_MARKER}%{{http_code}}")]);
+        if let Some(form) = &request.form {
+            command.args(["--data-raw", form]);
+        }
+        command
+            .arg(&request.url)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        let mut child = command.spawn().map_err(|error| match error.kind() {
+            io::ErrorKind::NotFound => HttpError::MissingCommand {
+                program: self.program.clone(),
+                reason: self.reason,
+            },
+            _ => HttpError::Io(error),
+        })?;
+        let headers: String = request
+            .headers
+            .iter()
+            .map(|(name, value)| format!("{name}: {value}\n"))
+            .collect();
+        child
+            .stdin
+            .take()
+            .expect("stdin is piped")
+            .write_all(headers.as_bytes())?;
+        let output = child.wait_with_output()?;
+
+        if !output.status.success() {
+            let message = String::from_utf8_lossy(&output.stderr);
+            return Err(HttpError::Io(io::Error::other(format!(
+                "{}: {}",
+                self.program,
+                message.trim()
+            ))));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let (body, status) = stdout
+            .rsplit_once(STATUS_MARKER)
+            .ok_or_else(|| HttpError::Malformed(format!("no status from {}", self.program)))?;
+
+        Ok(Response {
+            status: status
+                .trim()
+                .parse()
+                .map_err(|_| HttpError::Malformed(format!("status {status:?}")))?,
+            body: body.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use rstest::*;
+    use std::io::BufRead;
+    use std::io::BufReader;
+    use std::net::TcpListener;
+    use std::sync::mpsc::{self, Receiver};
+    use std::thread;
+
+    /// A request as the stub server saw it.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub(crate) struct Received {
+        pub method: String,
+        pub path: String,
+        pub headers: Vec<(String, String)>,
+        pub body: String,
+    }
+
+    impl Received {
+        pub fn header(&self, name: &str) -> Option<&str> {
+            self.headers
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.as_str())
+        }
+    }
+
+    fn receive(stream: &mut TcpStream) -> Received {
+        let mut reader = BufReader::new(stream);
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        let mut parts = line.split_whitespace();
+        let method = parts.next().unwrap().to_string();
+        let path = parts.next().unwrap().to_string();
+
+        let mut headers = Vec::new();
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            match line.trim_end().split_once(": ") {
+                Some((name, value)) => headers.push((name.to_string(), value.to_string())),
+                None => break,
+            }
+        }
+
+        let length = headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+            .map_or(0, |(_, value)| value.parse().unwrap());
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+
+        Received {
+            method,
+            path,
+            headers,
+            body: String::from_utf8(body).unwrap(),
+        }
+    }
+
+    /// Serves `responses` as `(status, body)`, one per connection, in order,
+    /// and returns its base URL and the requests it received.
+    pub(crate) fn stub_server(responses: Vec<(u16, String)>) -> (String, Receiver<Received>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, receiver) = mpsc::channel();
+
+        thread::spawn(move || {
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let received = receive(&mut stream);
+                write!(
+                    stream,
+                    "HTTP/1.1 {status} Stub\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+                sender.send(received).unwrap();
+            }
+        });
+
+        (url, receiver)
+    }
+
+    #[rstest]
+    #[case("http://localhost:8080/2023/day/1/input", Some(("localhost", 8080, "/2023/day/1/input")))]
+    #[case("http://example.com", Some(("example.com", 80, "/")))]
+    #[case("https://example.com/", None)]
+    #[case("http://:80/", None)]
+    fn urls(#[case] url: &str, #[case] expected: Option<(&str, u16, &str)>) {
+        assert_eq!(split_url(url), expected);
+    }
+
+    #[rstest]
+    #[case(
+        b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhello, and more",
+        200,
+        "hello"
+    )]
+    #[case(b"HTTP/1.1 404 Not Found\r\n\r\nnope\n", 404, "nope\n")]
+    #[case(
+        b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\nWiki\r\n6\r\npedia \r\n0\r\n\r\n",
+        200,
+        "Wikipedia "
+    )]
+    fn responses(#[case] raw: &[u8], #[case] status: u16, #[case] body: &str) {
+        let response = parse_response(raw).unwrap();
+
+        assert_eq!((response.status, response.body.as_str()), (status, body));
+    }
+
+    #[rstest]
+    #[case("http://localhost:8080", Backend::Plain)]
+    #[case("https://adventofcode.com", Backend::Curl)]
+    fn backend_follows_url(#[case] url: &str, #[case] expected: Backend) {
+        assert_eq!(Backend::for_url(url), expected);
+    }
+
+    #[rstest]
+    #[case(Reason::Url, "point AOC_BASE_URL at an http:// server")]
+    #[case(Reason::Configured, "change `http` in aoc.toml or AOC_HTTP")]
+    fn missing_curl(#[case] reason: Reason, #[case] advice: &str) {
+        let curl = Curl {
+            program: "aoc-test-no-such-curl".to_string(),
+            reason,
+        };
+
+        let error = curl
+            .send(&Request::get("https://example.com/"))
+            .unwrap_err();
+
+        assert!(error.to_string().ends_with(advice), "{error}");
+        assert!(matches!(
+            error,
+            HttpError::MissingCommand { program, reason: found } if program == curl.program && found == reason
+        ));
+    }
+
+    #[test]
+    fn plain_http_round_trip() {
+        let (url, requests) = stub_server(vec![(200, "answer\n".to_string())]);
+
+        let response = PlainHttp::default()
+            .send(&Request::post(format!("{url}/submit"), "level=1").header("Cookie", "x=y"))
+            .unwrap();
+
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, "answer\n");
+        let received = requests.recv().unwrap();
+        assert_eq!(received.method, "POST");
+        assert_eq!(received.path, "/submit");
+        assert_eq!(received.header("Cookie"), Some("x=y"));
+        assert_eq!(received.body, "level=1");
+    }
+}
@@ -2,6 +2,12 @@
 #![allow(unused_variables)]
 #![allow(unused_imports)]
 
+pub mod answers;
+pub mod bench;
+pub mod check;
+pub mod cli;
+pub mod client;
+pub mod cycle;
 pub mod day0;
 pub mod day1;
 pub mod day10;
@@ -20,3 +26,14 @@ pub mod day6;
 pub mod day7;
 pub mod day8;
 pub mod day9;
+pub mod examples;
+pub mod html;
+pub mod http;
+pub mod registry;
+pub mod report;
+pub mod runner;
+pub mod scaffold;
+pub mod submit;
+pub mod trace;
+pub mod watch;
+pub mod workspace;
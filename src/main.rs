@@ -1,5 +1,5 @@
-mod day1;
+use advent_of_code_2023::cli;
 
 fn main() {
-    println!("Hello, world!");
+    std::process::exit(cli::run(std::env::args().skip(1)));
 }
@@ -0,0 +1,145 @@
+//! Every day and part the runner knows about. `aoc new` adds entries to
+//! `SOLUTIONS`, so keep one `solution!` per line and the list sorted.
+//!
+//! A part registered as `parse_input => solve` has its parsing timed apart from
+//! solving; one registered by its `process` is timed as a whole.
+
+use std::time::{Duration, Instant};
+
+pub struct Solution {
+    pub day: u8,
+    pub part: u8,
+    pub run: fn(&str) -> String,
+    pub timed: fn(&str) -> Timed,
+}
+
+/// One run of a solution and how long it took.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timed {
+    pub answer: String,
+    /// `None` when the solution does not parse as a separate step.
+    pub parse: Option<Duration>,
+    pub solve: Duration,
+}
+
+impl Timed {
+    pub fn whole<R: ToString>(input: &str, process: impl Fn(&str) -> R) -> Timed {
+        let start = Instant::now();
+        let answer = process(input);
+        let solve = start.elapsed();
+
+        Timed {
+            answer: answer.to_string(),
+            parse: None,
+            solve,
+        }
+    }
+
+    pub fn stages<'a, T, R: ToString>(
+        input: &'a str,
+        parse: impl Fn(&'a str) -> T,
+        solve: impl Fn(&T) -> R,
+    ) -> Timed {
+        let start = Instant::now();
+        let parsed = parse(input);
+        let parsed_at = Instant::now();
+        let answer = solve(&parsed);
+        let solved_at = Instant::now();
+
+        Timed {
+            answer: answer.to_string(),
+            parse: Some(parsed_at - start),
+            solve: solved_at - parsed_at,
+        }
+    }
+
+    pub fn total(&self) -> Duration {
+        self.parse.unwrap_or_default() + self.solve
+    }
+}
+
+macro_rules! solution {
+    ($day:literal, $part:literal, $process:path) => {
+        Solution {
+            day: $day,
+            part: $part,
+            run: |input| $process(input).to_string(),
+            timed: |input| Timed::whole(input, $process),
+        }
+    };
+    ($day:literal, $part:literal, $parse:path => $solve:path) => {
+        Solution {
+            day: $day,
+            part: $part,
+            run: |input| $solve(&$parse(input)).to_string(),
+            timed: |input| Timed::stages(input, $parse, |parsed| $solve(parsed)),
+        }
+    };
+}
+
+#[rustfmt::skip]
+pub const SOLUTIONS: &[Solution] = &[
+    solution!(1, 1, crate::day1::pt1::process),
+    solution!(1, 2, crate::day1::pt2::process),
+    solution!(2, 1, crate::day2::pt1::parse_input => crate::day2::pt1::solve),
+    solution!(2, 2, crate::day2::pt2::parse_input => crate::day2::pt2::solve),
+    solution!(3, 1, crate::day3::pt1::parse_input => crate::day3::pt1::solve),
+    solution!(3, 2, crate::day3::pt2::parse_input => crate::day3::pt2::solve),
+    solution!(4, 1, crate::day4::pt1::parse_input => crate::day4::pt1::solve),
+    solution!(4, 2, crate::day4::pt2::parse_input => crate::day4::pt2::solve),
+    solution!(5, 1, crate::day5::pt1::parse_input => crate::day5::pt1::solve),
+    solution!(5, 2, crate::day5::pt2::parse_input => crate::day5::pt2::solve),
+    solution!(6, 1, crate::day6::pt1::parse_input => crate::day6::pt1::solve),
+    solution!(6, 2, crate::day6::pt2::parse_input => crate::day6::pt2::solve),
+    solution!(7, 1, crate::day7::pt1::parse_input => crate::day7::pt1::solve),
+    solution!(7, 2, crate::day7::pt2::parse_input => crate::day7::pt2::solve),
+    solution!(8, 1, crate::day8::pt1::parse_input => crate::day8::pt1::solve),
+    solution!(8, 2, crate::day8::pt2::process),
+    solution!(9, 1, crate::day9::pt1::parse_input => crate::day9::pt1::solve),
+    solution!(9, 2, crate::day9::pt2::parse_input => crate::day9::pt2::solve),
+    solution!(10, 1, crate::day10::pt1::parse_input => crate::day10::pt1::solve),
+    solution!(10, 2, crate::day10::pt2::process),
+    solution!(11, 1, crate::day11::pt1::parse_input => crate::day11::pt1::solve),
+    solution!(11, 2, crate::day11::pt2::parse_input => crate::day11::pt2::solve),
+    solution!(12, 1, crate::day12::pt1::parse_input => crate::day12::pt1::solve),
+    solution!(12, 2, crate::day12::pt2::parse_input => crate::day12::pt2::solve),
+    solution!(13, 1, crate::day13::pt1::parse_input => crate::day13::pt1::solve),
+    solution!(13, 2, crate::day13::pt2::parse_input => crate::day13::pt2::solve),
+    solution!(14, 1, crate::day14::pt1::parse_input => crate::day14::pt1::solve),
+    solution!(14, 2, crate::day14::pt2::parse_input => crate::day14::pt2::solve),
+    solution!(15, 1, crate::day15::pt1::process),
+    solution!(15, 2, crate::day15::pt2::process),
+    solution!(16, 1, crate::day16::pt1::process),
+    solution!(16, 2, crate::day16::pt2::process),
+    solution!(17, 1, crate::day17::pt1::process),
+    solution!(17, 2, crate::day17::pt2::process),
+];
+
+pub fn find(day: u8, part: u8) -> Option<&'static Solution> {
+    SOLUTIONS.iter().find(|s| s.day == day && s.part == part)
+}
+
+pub fn for_day(day: u8) -> impl Iterator<Item = &'static Solution> {
+    SOLUTIONS.iter().filter(move |s| s.day == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+
+    #[test]
+    fn sorted_and_unique() {
+        let keys: Vec<_> = SOLUTIONS.iter().map(|s| (s.day, s.part)).collect();
+
+        assert!(keys.iter().tuple_windows().all(|(a, b)| a < b));
+    }
+
+    #[test]
+    fn find_solution() {
+        let solution = find(1, 2).unwrap();
+
+        assert_eq!((solution.run)("two1nine"), "29");
+        assert!(find(0, 1).is_none());
+    }
+}
@@ -0,0 +1,292 @@
+//! Machine-readable reports of `aoc run --all`, for dashboards and CI.
+//!
+//! # JSON lines (`--format json`)
+//!
+//! One object per solution, in registry order, every key always present:
+//!
+//! | key        | type           | meaning                                          |
+//! |------------|----------------|--------------------------------------------------|
+//! | `day`      | number         | puzzle day                                       |
+//! | `part`     | number         | puzzle part                                      |
+//! | `title`    | string         | puzzle title from the README, `""` if unknown    |
+//! | `answer`   | string or null | null unless the solution ran to completion       |
+//! | `parse_ns` | number or null | parsing time; null when not timed separately     |
+//! | `solve_ns` | number or null | solving time, or the whole run if not split      |
+//! | `status`   | string         | see below                                        |
+//! | `expected` | string or null | the ledger's answer, set when `status` is `fail` |
+//! | `error`    | string or null | the panic message, set when `status` is `panic`  |
+//!
+//! `status` is one of `pass`, `fail`, `known_wrong` (an answer the ledger
+//! records as rejected), `unknown` (the ledger has no answer to check
+//! against), `no_input` or `panic`.
+//!
+//! Keys may be added, but none of the above will be renamed or removed.
+//!
+//! # JUnit XML (`--format junit`)
+//!
+//! One `<testsuite name="aoc">` with a `<testcase name="part N"
+//! classname="dayN">` per solution. `fail` and `known_wrong` are
+//! `<failure>`s, `panic` an `<error>` and `no_input` is `<skipped>`. The
+//! answer, when there is one, is the test case's `<system-out>`.
+
+use std::io::{self, Write};
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::answers::Verdict;
+use crate::runner::{Outcome, Status};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Table,
+    Json,
+    Junit,
+}
+
+impl std::str::FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(Format::Table),
+            "json" => Ok(Format::Json),
+            "junit" => Ok(Format::Junit),
+            _ => Err(format!(
+                "unknown format {s:?}, expected table, json or junit"
+            )),
+        }
+    }
+}
+
+/// One line of the JSON report; see the module documentation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Record<'a> {
+    pub day: u8,
+    pub part: u8,
+    pub title: &'a str,
+    pub answer: Option<&'a str>,
+    pub parse_ns: Option<u64>,
+    pub solve_ns: Option<u64>,
+    pub status: &'static str,
+    pub expected: Option<&'a str>,
+    pub error: Option<&'a str>,
+}
+
+fn nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+fn status_name(status: &Status) -> &'static str {
+    match status {
+        Status::Checked(Verdict::Pass) => "pass",
+        Status::Checked(Verdict::Fail { .. }) => "fail",
+        Status::Checked(Verdict::KnownWrong) => "known_wrong",
+        Status::Checked(Verdict::Unknown) => "unknown",
+        Status::NoInput => "no_input",
+        Status::Checked(Verdict::Panicked(_)) | Status::Panicked(_) => "panic",
+    }
+}
+
+impl<'a> From<&'a Outcome> for Record<'a> {
+    fn from(outcome: &'a Outcome) -> Self {
+        let timed = outcome.timed.as_ref();
+
+        Record {
+            day: outcome.day,
+            part: outcome.part,
+            title: &outcome.title,
+            answer: timed.map(|x| x.answer.as_str()),
+            parse_ns: timed.and_then(|x| x.parse).map(nanos),
+            solve_ns: timed.map(|x| nanos(x.solve)),
+            status: status_name(&outcome.status),
+            expected: match &outcome.status {
+                Status::Checked(Verdict::Fail { expected }) => Some(expected),
+                _ => None,
+            },
+            error: match &outcome.status {
+                Status::Checked(Verdict::Panicked(message)) | Status::Panicked(message) => {
+                    Some(message)
+                }
+                _ => None,
+            },
+        }
+    }
+}
+
+pub fn write_json(out: &mut impl Write, outcomes: &[Outcome]) -> io::Result<()> {
+    for outcome in outcomes {
+        serde_json::to_writer(&mut *out, &Record::from(outcome))?;
+        writeln!(out)?;
+    }
+
+    Ok(())
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Not allowed in XML 1.0 at all.
+            c if c.is_control() && !matches!(c, '\t' | '\n' | '\r') => {}
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+fn seconds(duration: Duration) -> String {
+    format!("{:.6}", duration.as_secs_f64())
+}
+
+pub fn write_junit(out: &mut impl Write, outcomes: &[Outcome]) -> io::Result<()> {
+    let count = |select: fn(&Status) -> bool| {
+        outcomes
+            .iter()
+            .filter(|outcome| select(&outcome.status))
+            .count()
+    };
+    let total: Duration = outcomes
+        .iter()
+        .filter_map(|outcome| outcome.timed.as_ref())
+        .map(|timed| timed.total())
+        .sum();
+
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        out,
+        r#"<testsuite name="aoc" tests="{}" failures="{}" errors="{}" skipped="{}" time="{}">"#,
+        outcomes.len(),
+        count(|status| matches!(status, Status::Checked(verdict) if verdict.is_failure())),
+        count(|status| matches!(status, Status::Panicked(_))),
+        count(|status| matches!(status, Status::NoInput)),
+        seconds(total),
+    )?;
+
+    for outcome in outcomes {
+        let timed = outcome.timed.as_ref();
+        writeln!(
+            out,
+            r#"  <testcase name="part {}" classname="day{}" time="{}">"#,
+            outcome.part,
+            outcome.day,
+            seconds(timed.map_or(Duration::ZERO, |x| x.total())),
+        )?;
+
+        match &outcome.status {
+            Status::Checked(verdict) if verdict.is_failure() => writeln!(
+                out,
+                r#"    <failure message="{}"/>"#,
+                escape(&verdict.to_string())
+            )?,
+            Status::Checked(_) => {}
+            Status::NoInput => writeln!(out, r#"    <skipped message="no input"/>"#)?,
+            Status::Panicked(message) => {
+                writeln!(out, r#"    <error message="{}"/>"#, escape(message))?;
+            }
+        }
+        if let Some(timed) = timed {
+            writeln!(
+                out,
+                "    <system-out>{}</system-out>",
+                escape(&timed.answer)
+            )?;
+        }
+
+        writeln!(out, "  </testcase>")?;
+    }
+
+    writeln!(out, "</testsuite>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::Timed;
+
+    fn outcomes() -> Vec<Outcome> {
+        vec![
+            Outcome {
+                day: 1,
+                part: 1,
+                title: "Trebuchet?!".to_string(),
+                timed: Some(Timed {
+                    answer: "142".to_string(),
+                    parse: None,
+                    solve: Duration::from_nanos(1500),
+                }),
+                status: Status::Checked(Verdict::Fail {
+                    expected: "<143>".to_string(),
+                }),
+            },
+            Outcome {
+                day: 1,
+                part: 2,
+                title: "Trebuchet?!".to_string(),
+                timed: None,
+                status: Status::Panicked("index out of \"bounds\"".to_string()),
+            },
+            Outcome {
+                day: 2,
+                part: 1,
+                title: String::new(),
+                timed: Some(Timed {
+                    answer: "8".to_string(),
+                    parse: Some(Duration::from_nanos(10)),
+                    solve: Duration::from_nanos(20),
+                }),
+                status: Status::Checked(Verdict::Pass),
+            },
+            Outcome {
+                day: 3,
+                part: 1,
+                title: String::new(),
+                timed: None,
+                status: Status::NoInput,
+            },
+        ]
+    }
+
+    #[test]
+    fn json_schema() {
+        let mut out = Vec::new();
+
+        write_json(&mut out, &outcomes()).unwrap();
+
+        let lines: Vec<&str> = std::str::from_utf8(&out).unwrap().lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                r#"{"day":1,"part":1,"title":"Trebuchet?!","answer":"142","parse_ns":null,"solve_ns":1500,"status":"fail","expected":"<143>","error":null}"#,
+                r#"{"day":1,"part":2,"title":"Trebuchet?!","answer":null,"parse_ns":null,"solve_ns":null,"status":"panic","expected":null,"error":"index out of \"bounds\""}"#,
+                r#"{"day":2,"part":1,"title":"","answer":"8","parse_ns":10,"solve_ns":20,"status":"pass","expected":null,"error":null}"#,
+                r#"{"day":3,"part":1,"title":"","answer":null,"parse_ns":null,"solve_ns":null,"status":"no_input","expected":null,"error":null}"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn junit() {
+        let mut out = Vec::new();
+
+        write_junit(&mut out, &outcomes()).unwrap();
+
+        let xml = String::from_utf8(out).unwrap();
+        assert!(xml.contains(
+            r#"<testsuite name="aoc" tests="4" failures="1" errors="1" skipped="1" time="0.000002">"#
+        ));
+        assert!(xml.contains(r#"<failure message="FAIL (expected &lt;143&gt;)"/>"#));
+        assert!(xml.contains(r#"<error message="index out of &quot;bounds&quot;"/>"#));
+        assert!(xml.contains(r#"<skipped message="no input"/>"#));
+        assert!(xml.contains(r#"<testcase name="part 1" classname="day2" time="0.000000">"#));
+        assert!(xml.contains("<system-out>142</system-out>"));
+        assert!(xml.trim_end().ends_with("</testsuite>"));
+    }
+}
@@ -0,0 +1,262 @@
+//! `aoc run --all`: every registered solution on its real input, spread over
+//! a pool of threads, with a panic in one solution reported as its status
+//! instead of taking the others down.
+
+use core::fmt;
+use std::any::Any;
+use std::fs;
+use std::num::NonZeroUsize;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use crate::answers::{Ledger, Verdict};
+use crate::examples;
+use crate::registry::{Solution, Timed};
+use crate::workspace::Workspace;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Checked(Verdict),
+    NoInput,
+    Panicked(String),
+}
+
+impl Status {
+    pub fn is_failure(&self) -> bool {
+        match self {
+            Status::Checked(verdict) => verdict.is_failure(),
+            Status::NoInput => false,
+            Status::Panicked(_) => true,
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Checked(verdict) => write!(f, "{verdict}"),
+            Status::NoInput => write!(f, "no input"),
+            Status::Panicked(message) => write!(f, "PANIC: {message}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    pub day: u8,
+    pub part: u8,
+    pub title: String,
+    /// `None` unless the solution ran to completion.
+    pub timed: Option<Timed>,
+    pub status: Status,
+}
+
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+    match (
+        payload.downcast_ref::<&str>(),
+        payload.downcast_ref::<String>(),
+    ) {
+        (Some(message), _) => message.to_string(),
+        (_, Some(message)) => message.clone(),
+        _ => "(no message)".to_string(),
+    }
+}
+
+/// Runs `f`, returning the message of any panic as an error.
+pub(crate) fn catch<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| panic_message(payload.as_ref()))
+}
+
+/// Runs `f` with the panic hook silenced, so panics that `f` catches and
+/// reports itself are not also printed as they happen.
+pub fn contain<T>(f: impl FnOnce() -> T) -> T {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    panic::set_hook(hook);
+
+    result.unwrap_or_else(|payload| panic::resume_unwind(payload))
+}
+
+fn title(workspace: &Workspace, day: u8) -> String {
+    examples::read_readme(workspace, day).map_or_else(|_| String::new(), |puzzle| puzzle.title)
+}
+
+fn run_one(workspace: &Workspace, ledger: &Ledger, solution: &Solution) -> Outcome {
+    let outcome = |timed, status| Outcome {
+        day: solution.day,
+        part: solution.part,
+        title: title(workspace, solution.day),
+        timed,
+        status,
+    };
+
+    let input = match fs::read_to_string(workspace.input_path(solution.day)) {
+        Ok(input) if !input.trim().is_empty() => input,
+        _ => return outcome(None, Status::NoInput),
+    };
+
+    match catch(|| (solution.timed)(&input)) {
+        Ok(timed) => {
+            let verdict = ledger.check(solution.day, solution.part, &timed.answer);
+            outcome(Some(timed), Status::Checked(verdict))
+        }
+        Err(message) => outcome(None, Status::Panicked(message)),
+    }
+}
+
+pub fn default_threads() -> usize {
+    thread::available_parallelism().map_or(1, NonZeroUsize::get)
+}
+
+/// Runs `solutions` on `threads` worker threads and returns their outcomes
+/// in the order given.
+pub fn run_all(
+    workspace: &Workspace,
+    ledger: &Ledger,
+    solutions: &[Solution],
+    threads: usize,
+) -> Vec<Outcome> {
+    let next = AtomicUsize::new(0);
+    let outcomes = Mutex::new(Vec::with_capacity(solutions.len()));
+
+    thread::scope(|scope| {
+        for _ in 0..threads.clamp(1, solutions.len().max(1)) {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(solution) = solutions.get(index) else {
+                    break;
+                };
+
+                let outcome = run_one(workspace, ledger, solution);
+                outcomes.lock().unwrap().push((index, outcome));
+            });
+        }
+    });
+
+    let mut outcomes = outcomes.into_inner().unwrap();
+    outcomes.sort_by_key(|(index, _)| *index);
+    outcomes.into_iter().map(|(_, outcome)| outcome).collect()
+}
+
+/// The outcomes of a run, displayed as a table.
+pub struct Summary<'a>(pub &'a [Outcome]);
+
+fn format_duration(duration: Option<Duration>) -> String {
+    duration.map_or("-".to_string(), |x| format!("{x:.3?}"))
+}
+
+impl fmt::Display for Summary<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let title_width = self
+            .0
+            .iter()
+            .map(|outcome| outcome.title.len())
+            .max()
+            .unwrap_or(0)
+            .max("title".len());
+
+        writeln!(
+            f,
+            "{:>3} {:>4}  {:<title_width$}  {:<16}  {:>10} {:>10}  status",
+            "day", "part", "title", "answer", "parse", "solve"
+        )?;
+
+        for outcome in self.0 {
+            let timed = outcome.timed.as_ref();
+            writeln!(
+                f,
+                "{:>3} {:>4}  {:<title_width$}  {:<16}  {:>10} {:>10}  {}",
+                outcome.day,
+                outcome.part,
+                outcome.title,
+                timed.map_or("-", |x| x.answer.as_str()),
+                format_duration(timed.and_then(|x| x.parse)),
+                format_duration(timed.map(|x| x.solve)),
+                outcome.status,
+            )?;
+        }
+
+        let failures = self.0.iter().filter(|x| x.status.is_failure()).count();
+        write!(f, "{} solutions, {failures} failed", self.0.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scaffold::tests::scratch_dir;
+
+    fn answer(input: &str) -> String {
+        input.trim().to_string()
+    }
+
+    fn explode(input: &str) -> String {
+        panic!("no solution for {}", input.trim())
+    }
+
+    const SOLUTIONS: &[Solution] = &[
+        Solution {
+            day: 1,
+            part: 1,
+            run: answer,
+            timed: |input| Timed::whole(input, answer),
+        },
+        Solution {
+            day: 1,
+            part: 2,
+            run: explode,
+            timed: |input| Timed::whole(input, explode),
+        },
+        Solution {
+            day: 2,
+            part: 1,
+            run: answer,
+            timed: |input| Timed::whole(input, answer),
+        },
+    ];
+
+    #[test]
+    fn caught_panics_keep_their_message() {
+        let caught = contain(|| catch(|| explode("42\n")));
+
+        assert_eq!(caught, Err("no solution for 42".to_string()));
+        assert_eq!(contain(|| catch(|| answer("42\n"))), Ok("42".to_string()));
+    }
+
+    #[test]
+    fn panics_are_contained() {
+        let workspace = Workspace::new(scratch_dir("runner"));
+        fs::create_dir_all(workspace.day_dir(1)).unwrap();
+        fs::write(workspace.input_path(1), "42\n").unwrap();
+        fs::write(
+            workspace.readme_path(1),
+            "--- Day 1: Test Day ---\nSome text.\n",
+        )
+        .unwrap();
+        let mut ledger = Ledger::default();
+        ledger.entry_mut(1, 1).expected = Some("42".to_string());
+
+        let outcomes = run_all(&workspace, &ledger, SOLUTIONS, 2);
+
+        let statuses: Vec<_> = outcomes
+            .iter()
+            .map(|x| (x.day, x.part, x.status.clone()))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                (1, 1, Status::Checked(Verdict::Pass)),
+                (1, 2, Status::Panicked("no solution for 42".to_string())),
+                (2, 1, Status::NoInput),
+            ]
+        );
+        assert_eq!(outcomes[0].title, "Test Day");
+        assert!(Summary(&outcomes)
+            .to_string()
+            .ends_with("3 solutions, 1 failed"));
+    }
+}
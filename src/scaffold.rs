@@ -0,0 +1,339 @@
+use core::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::workspace::Workspace;
+
+const TEMPLATE_FILES: [&str; 3] = ["mod.rs", "pt1.rs", "pt2.rs"];
+const PLACEHOLDER_FILES: [&str; 2] = ["input.txt", "README.txt"];
+
+#[derive(Debug)]
+pub enum ScaffoldError {
+    InvalidDay(u8),
+    AlreadyExists(PathBuf),
+    AlreadyRegistered(u8),
+    MissingAnchor(PathBuf),
+    Io(PathBuf, io::Error),
+}
+
+impl fmt::Display for ScaffoldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScaffoldError::InvalidDay(day) => write!(f, "day {day} is not between 1 and 25"),
+            ScaffoldError::AlreadyExists(path) => {
+                write!(f, "{} already exists, not overwriting it", path.display())
+            }
+            ScaffoldError::AlreadyRegistered(day) => {
+                write!(f, "day {day} is already registered")
+            }
+            ScaffoldError::MissingAnchor(path) => {
+                write!(
+                    f,
+                    "could not find where to register the day in {}",
+                    path.display()
+                )
+            }
+            ScaffoldError::Io(path, error) => write!(f, "{}: {error}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for ScaffoldError {}
+
+fn read(path: PathBuf) -> Result<String, ScaffoldError> {
+    fs::read_to_string(&path).map_err(|error| ScaffoldError::Io(path, error))
+}
+
+fn write(path: PathBuf, contents: &str) -> Result<(), ScaffoldError> {
+    fs::write(&path, contents).map_err(|error| ScaffoldError::Io(path, error))
+}
+
+fn rename(from: PathBuf, to: PathBuf) -> Result<(), ScaffoldError> {
+    fs::rename(&from, &to).map_err(|error| ScaffoldError::Io(to, error))
+}
+
+/// Where the new version of `path` is written before it replaces `path`.
+fn staging_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".new");
+    path.with_file_name(name)
+}
+
+/// Adds `pub mod dayN;` to `lib.rs`, keeping the `pub mod` lines sorted.
+fn register_module(lib_rs: &str, day: u8) -> Option<String> {
+    let module = format!("day{day}");
+    let line = format!("pub mod {module};");
+
+    let modules: Vec<(usize, &str)> = lib_rs
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let name = line.strip_prefix("pub mod ")?.strip_suffix(';')?;
+            Some((index, name))
+        })
+        .collect();
+
+    let (last, _) = modules.last()?;
+    let index = modules
+        .iter()
+        .find(|(_, name)| *name > module.as_str())
+        .map_or(last + 1, |(index, _)| *index);
+
+    Some(insert_line(lib_rs, index, &line))
+}
+
+/// Adds one `solution!` line per part to the registry, keeping it sorted.
+fn register_solutions(registry_rs: &str, day: u8) -> Option<String> {
+    let entry = |line: &str| -> Option<u8> {
+        let rest = line.trim_start().strip_prefix("solution!(")?;
+        rest.split(',').next()?.trim().parse().ok()
+    };
+
+    let entries: Vec<(usize, u8)> = registry_rs
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| entry(line).map(|d| (index, d)))
+        .collect();
+
+    let (last, _) = entries.last()?;
+    let index = entries
+        .iter()
+        .find(|(_, d)| *d > day)
+        .map_or(last + 1, |(index, _)| *index);
+
+    let lines = format!(
+        "    solution!({day}, 1, crate::day{day}::pt1::process),\n    solution!({day}, 2, crate::day{day}::pt2::process),"
+    );
+
+    Some(insert_line(registry_rs, index, &lines))
+}
+
+fn insert_line(contents: &str, index: usize, line: &str) -> String {
+    let mut lines: Vec<&str> = contents.lines().collect();
+    lines.insert(index, line);
+
+    let mut result = lines.join("\n");
+    if contents.ends_with('\n') {
+        result.push('\n');
+    }
+    result
+}
+
+/// Writes the day's files into `staged_dir` and the registrations beside the
+/// files they replace.
+fn write_staged(
+    workspace: &Workspace,
+    staged_dir: &Path,
+    templates: Vec<(&str, String)>,
+    lib_rs: &str,
+    registry_rs: &str,
+) -> Result<(), ScaffoldError> {
+    fs::create_dir_all(staged_dir)
+        .map_err(|error| ScaffoldError::Io(staged_dir.to_path_buf(), error))?;
+    for (file, contents) in templates {
+        write(staged_dir.join(file), &contents)?;
+    }
+    for file in PLACEHOLDER_FILES {
+        write(staged_dir.join(file), "")?;
+    }
+
+    write(staging_path(&workspace.lib_rs()), lib_rs)?;
+    write(staging_path(&workspace.registry_rs()), registry_rs)
+}
+
+/// Creates `src/dayN` from the day0 template, with empty input and README
+/// placeholders, and registers it in `lib.rs` and the solution registry.
+///
+/// Everything is first written beside where it goes, as `dayN.new`,
+/// `lib.rs.new` and `registry.rs.new`, and only renamed into place once all
+/// of it is written. A failure before that removes what was staged and leaves
+/// the tree as it was. Only a failed rename can leave a day half registered.
+pub fn new_day(workspace: &Workspace, day: u8) -> Result<PathBuf, ScaffoldError> {
+    if !(1..=25).contains(&day) {
+        return Err(ScaffoldError::InvalidDay(day));
+    }
+
+    let day_dir = workspace.day_dir(day);
+    if day_dir.exists() {
+        return Err(ScaffoldError::AlreadyExists(day_dir));
+    }
+
+    let lib_rs = read(workspace.lib_rs())?;
+    if lib_rs
+        .lines()
+        .any(|line| line == format!("pub mod day{day};"))
+    {
+        return Err(ScaffoldError::AlreadyRegistered(day));
+    }
+    let registry_rs = read(workspace.registry_rs())?;
+    if registry_rs.contains(&format!("crate::day{day}::")) {
+        return Err(ScaffoldError::AlreadyRegistered(day));
+    }
+
+    let lib_rs = register_module(&lib_rs, day)
+        .ok_or_else(|| ScaffoldError::MissingAnchor(workspace.lib_rs()))?;
+    let registry_rs = register_solutions(&registry_rs, day)
+        .ok_or_else(|| ScaffoldError::MissingAnchor(workspace.registry_rs()))?;
+
+    let templates = TEMPLATE_FILES
+        .iter()
+        .map(|&file| Ok((file, read(workspace.template_dir().join(file))?)))
+        .collect::<Result<Vec<_>, ScaffoldError>>()?;
+
+    let staged_dir = staging_path(&day_dir);
+    // Left over from a run that was cut short.
+    let _ = fs::remove_dir_all(&staged_dir);
+
+    let staged = write_staged(workspace, &staged_dir, templates, &lib_rs, &registry_rs);
+    if let Err(error) = staged {
+        let _ = fs::remove_dir_all(&staged_dir);
+        let _ = fs::remove_file(staging_path(&workspace.lib_rs()));
+        let _ = fs::remove_file(staging_path(&workspace.registry_rs()));
+        return Err(error);
+    }
+
+    rename(staged_dir, day_dir.clone())?;
+    rename(staging_path(&workspace.lib_rs()), workspace.lib_rs())?;
+    rename(
+        staging_path(&workspace.registry_rs()),
+        workspace.registry_rs(),
+    )?;
+
+    Ok(day_dir)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use rstest::*;
+
+    /// A fresh directory under the system temp dir, unique to `name` and this
+    /// test process.
+    pub(crate) fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn scratch_workspace(name: &str) -> Workspace {
+        let workspace = Workspace::new(scratch_dir(name));
+
+        fs::create_dir_all(workspace.template_dir()).unwrap();
+        for file in TEMPLATE_FILES {
+            fs::write(workspace.template_dir().join(file), format!("// {file}\n")).unwrap();
+        }
+        fs::write(
+            workspace.lib_rs(),
+            "pub mod cycle;\npub mod day0;\npub mod day1;\npub mod day30;\npub mod registry;\n",
+        )
+        .unwrap();
+        fs::write(
+            workspace.registry_rs(),
+            "pub const SOLUTIONS: &[Solution] = &[\n    solution!(1, 1, crate::day1::pt1::process),\n    solution!(30, 1, crate::day30::pt1::process),\n];\n",
+        )
+        .unwrap();
+
+        workspace
+    }
+
+    #[rstest]
+    #[case(
+        "pub mod cycle;\npub mod day1;\npub mod day2;\n",
+        18,
+        "pub mod cycle;\npub mod day1;\npub mod day18;\npub mod day2;\n"
+    )]
+    #[case(
+        "pub mod day1;\npub mod registry;",
+        3,
+        "pub mod day1;\npub mod day3;\npub mod registry;"
+    )]
+    #[case(
+        "#![allow(dead_code)]\n\npub mod day1;\n",
+        9,
+        "#![allow(dead_code)]\n\npub mod day1;\npub mod day9;\n"
+    )]
+    fn module_order(#[case] lib_rs: &str, #[case] day: u8, #[case] expected: &str) {
+        assert_eq!(register_module(lib_rs, day).unwrap(), expected);
+    }
+
+    #[test]
+    fn creates_day() {
+        let workspace = scratch_workspace("scaffold-creates");
+
+        let day_dir = new_day(&workspace, 25).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(day_dir.join("pt1.rs")).unwrap(),
+            "// pt1.rs\n"
+        );
+        assert_eq!(fs::read_to_string(day_dir.join("input.txt")).unwrap(), "");
+        assert!(day_dir.join("README.txt").exists());
+        assert_eq!(
+            fs::read_to_string(workspace.lib_rs()).unwrap(),
+            "pub mod cycle;\npub mod day0;\npub mod day1;\npub mod day25;\npub mod day30;\npub mod registry;\n"
+        );
+        assert_eq!(
+            fs::read_to_string(workspace.registry_rs()).unwrap(),
+            "pub const SOLUTIONS: &[Solution] = &[\n    solution!(1, 1, crate::day1::pt1::process),\n    solution!(25, 1, crate::day25::pt1::process),\n    solution!(25, 2, crate::day25::pt2::process),\n    solution!(30, 1, crate::day30::pt1::process),\n];\n"
+        );
+    }
+
+    #[test]
+    fn refuses_to_overwrite() {
+        let workspace = scratch_workspace("scaffold-overwrite");
+        fs::create_dir_all(workspace.day_dir(24)).unwrap();
+        fs::write(workspace.input_path(24), "my input").unwrap();
+
+        let result = new_day(&workspace, 24);
+
+        assert!(matches!(result, Err(ScaffoldError::AlreadyExists(_))));
+        assert_eq!(
+            fs::read_to_string(workspace.input_path(24)).unwrap(),
+            "my input"
+        );
+    }
+
+    #[test]
+    fn failure_leaves_nothing_behind() {
+        let workspace = scratch_workspace("scaffold-failure");
+        let lib_rs = fs::read_to_string(workspace.lib_rs()).unwrap();
+        // Writing the staged registry fails once everything else is staged.
+        fs::create_dir_all(staging_path(&workspace.registry_rs())).unwrap();
+
+        let result = new_day(&workspace, 25);
+
+        assert!(matches!(result, Err(ScaffoldError::Io(_, _))));
+        assert!(!workspace.day_dir(25).exists());
+        assert!(!staging_path(&workspace.day_dir(25)).exists());
+        assert!(!staging_path(&workspace.lib_rs()).exists());
+        assert_eq!(fs::read_to_string(workspace.lib_rs()).unwrap(), lib_rs);
+    }
+
+    #[rstest]
+    #[case(1)]
+    #[case(23)]
+    fn refuses_registered_day(#[case] day: u8) {
+        let workspace = scratch_workspace(&format!("scaffold-registered-{day}"));
+        let lib_rs = fs::read_to_string(workspace.lib_rs()).unwrap();
+        fs::write(workspace.lib_rs(), format!("{lib_rs}pub mod day23;\n")).unwrap();
+
+        let result = new_day(&workspace, day);
+
+        assert!(matches!(result, Err(ScaffoldError::AlreadyRegistered(_))));
+        assert!(!workspace.day_dir(day).exists());
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(26)]
+    fn refuses_invalid_day(#[case] day: u8) {
+        let workspace = scratch_workspace(&format!("scaffold-invalid-{day}"));
+
+        assert!(matches!(
+            new_day(&workspace, day),
+            Err(ScaffoldError::InvalidDay(_))
+        ));
+    }
+}
@@ -0,0 +1,541 @@
+//! `aoc submit`: sends an answer to the puzzle server, unless we already know
+//! how that would go, and keeps what it says in the answer ledger.
+//!
+//! The server makes us wait after every wrong answer and refuses anything sent
+//! sooner. We keep that deadline in `target/aoc/cooldown.toml` and refuse to
+//! submit before it has passed, rather than burn a request on being told so.
+
+use core::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::answers::{Entry, Ledger, LedgerError, State};
+use crate::client::{Client, ClientError};
+use crate::registry::Solution;
+use crate::runner;
+use crate::workspace::Workspace;
+
+/// What the server made of an answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reply {
+    Right,
+    Wrong,
+    TooHigh,
+    TooLow,
+    /// Sent too soon after the last answer; try again after this long.
+    Wait(Duration),
+    /// The part was solved already, so the answer was not checked.
+    AlreadySolved,
+}
+
+impl fmt::Display for Reply {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Reply::Right => write!(f, "right answer"),
+            Reply::Wrong => write!(f, "wrong answer"),
+            Reply::TooHigh => write!(f, "wrong answer, too high"),
+            Reply::TooLow => write!(f, "wrong answer, too low"),
+            Reply::Wait(wait) => write!(f, "sent too soon, wait {}s", wait.as_secs()),
+            Reply::AlreadySolved => write!(f, "already solved"),
+        }
+    }
+}
+
+/// A reply and how long to wait before the next answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Feedback {
+    pub reply: Reply,
+    pub cooldown: Option<Duration>,
+}
+
+#[derive(Debug)]
+pub enum SubmitError {
+    /// The ledger already has the answer.
+    Solved {
+        expected: String,
+    },
+    /// The ledger records this answer as rejected.
+    KnownWrong(String),
+    /// An answer at least as high as one already rejected as too high.
+    NotBelow {
+        answer: String,
+        too_high: String,
+    },
+    /// An answer at most as low as one already rejected as too low.
+    NotAbove {
+        answer: String,
+        too_low: String,
+    },
+    /// The server's cooldown has not passed yet.
+    Cooldown(Duration),
+    /// The server's page did not say anything we recognize.
+    UnknownReply(String),
+    /// The day has no input to run the solution on.
+    NoInput(PathBuf),
+    /// The solution panicked, so there is no answer to send.
+    Panicked(String),
+    Client(ClientError),
+    Ledger(LedgerError),
+    Io(PathBuf, io::Error),
+}
+
+impl fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubmitError::Solved { expected } => {
+                write!(f, "already solved, the answer is {expected}")
+            }
+            SubmitError::KnownWrong(answer) => {
+                write!(f, "{answer} was already rejected, not sending it again")
+            }
+            SubmitError::NotBelow { answer, too_high } => write!(
+                f,
+                "{answer} is not below {too_high}, which was already too high, not sending it"
+            ),
+            SubmitError::NotAbove { answer, too_low } => write!(
+                f,
+                "{answer} is not above {too_low}, which was already too low, not sending it"
+            ),
+            SubmitError::Cooldown(wait) => {
+                write!(f, "the server wants us to wait another {}s", wait.as_secs())
+            }
+            SubmitError::UnknownReply(text) => write!(f, "unrecognized reply: {text}"),
+            SubmitError::NoInput(path) => write!(f, "no input in {}", path.display()),
+            SubmitError::Panicked(message) => {
+                write!(f, "the solution panicked, not submitting: {message}")
+            }
+            SubmitError::Client(error) => write!(f, "{error}"),
+            SubmitError::Ledger(error) => write!(f, "{error}"),
+            SubmitError::Io(path, error) => write!(f, "{}: {error}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for SubmitError {}
+
+impl From<ClientError> for SubmitError {
+    fn from(error: ClientError) -> Self {
+        SubmitError::Client(error)
+    }
+}
+
+impl From<LedgerError> for SubmitError {
+    fn from(error: LedgerError) -> Self {
+        SubmitError::Ledger(error)
+    }
+}
+
+/// The text of the page's `<article>`, or of the whole page if it has none,
+/// without tags and with whitespace collapsed.
+fn article_text(html: &str) -> String {
+    let article = html
+        .split_once("<article")
+        .and_then(|(_, rest)| rest.split_once('>'))
+        .and_then(|(_, rest)| rest.split_once("</article>"))
+        .map_or(html, |(article, _)| article);
+
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in article.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn number(word: &str) -> Option<u64> {
+    const WORDS: [&str; 10] = [
+        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+    ];
+
+    word.parse().ok().or_else(|| {
+        WORDS
+            .iter()
+            .position(|x| *x == word)
+            .map(|index| index as u64 + 1)
+    })
+}
+
+/// `You have 1m 23s left to wait` or `please wait 5 minutes`.
+fn parse_wait(text: &str) -> Option<Duration> {
+    let left = text
+        .split_once("You have ")
+        .and_then(|(_, rest)| rest.split_once(" left to wait"));
+    if let Some((amount, _)) = left {
+        let seconds = amount
+            .split_whitespace()
+            .map(|part| {
+                let (value, unit) = part.split_at(part.find(|c: char| !c.is_ascii_digit())?);
+                let value: u64 = value.parse().ok()?;
+                match unit {
+                    "h" => Some(value * 3600),
+                    "m" => Some(value * 60),
+                    "s" => Some(value),
+                    _ => None,
+                }
+            })
+            .sum::<Option<u64>>()?;
+
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let text = text.to_lowercase();
+    let (_, rest) = text.split_once("please wait ")?;
+    let mut words = rest.split_whitespace();
+    let value = number(words.next()?)?;
+    match words.next()?.trim_end_matches('s') {
+        "minute" => Some(Duration::from_secs(value * 60)),
+        "second" => Some(Duration::from_secs(value)),
+        _ => None,
+    }
+}
+
+/// Reads the server's answer page, `None` if it says nothing we know.
+pub fn parse_reply(html: &str) -> Option<Feedback> {
+    let text = article_text(html);
+    let cooldown = parse_wait(&text);
+
+    let reply = if text.contains("That's the right answer") {
+        Reply::Right
+    } else if text.contains("You gave an answer too recently") {
+        Reply::Wait(cooldown?)
+    } else if text.contains("Did you already complete it") {
+        Reply::AlreadySolved
+    } else if text.contains("That's not the right answer") {
+        if text.contains("your answer is too high") {
+            Reply::TooHigh
+        } else if text.contains("your answer is too low") {
+            Reply::TooLow
+        } else {
+            Reply::Wrong
+        }
+    } else {
+        return None;
+    };
+
+    Some(Feedback { reply, cooldown })
+}
+
+/// When the server will take the next answer, in seconds since the epoch.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct Cooldown {
+    until: u64,
+}
+
+fn seconds_since_epoch(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map_or(0, |x| x.as_secs())
+}
+
+impl Cooldown {
+    fn load(workspace: &Workspace) -> Cooldown {
+        fs::read_to_string(workspace.cooldown_path())
+            .ok()
+            .and_then(|text| toml::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, workspace: &Workspace) -> Result<(), SubmitError> {
+        let path = workspace.cooldown_path();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|error| SubmitError::Io(dir.to_path_buf(), error))?;
+        }
+
+        let text = toml::to_string(self).expect("cooldown should serialize");
+        fs::write(&path, text).map_err(|error| SubmitError::Io(path, error))
+    }
+
+    fn remaining(&self, now: SystemTime) -> Option<Duration> {
+        let now = seconds_since_epoch(now);
+        (self.until > now).then(|| Duration::from_secs(self.until - now))
+    }
+}
+
+/// Runs `solution` on its day's input for the answer to submit. A panic is
+/// returned as an error, like `aoc run --all` and `aoc check` report it.
+pub fn run_solution(workspace: &Workspace, solution: &Solution) -> Result<String, SubmitError> {
+    let path = workspace.input_path(solution.day);
+    let input = fs::read_to_string(&path)
+        .ok()
+        .filter(|input| !input.trim().is_empty())
+        .ok_or(SubmitError::NoInput(path))?;
+
+    runner::catch(|| (solution.run)(&input)).map_err(SubmitError::Panicked)
+}
+
+fn numeric(answer: &str) -> Option<i128> {
+    answer.trim().parse().ok()
+}
+
+/// Refuses `answer` if an earlier too high or too low reply already rules
+/// it out. Answers that are not numbers have no bounds.
+fn check_bounds(entry: &Entry, answer: &str) -> Result<(), SubmitError> {
+    let Some(value) = numeric(answer) else {
+        return Ok(());
+    };
+    let bound = |bound: &Option<String>| bound.as_deref().and_then(numeric);
+
+    if bound(&entry.too_high).is_some_and(|too_high| value >= too_high) {
+        return Err(SubmitError::NotBelow {
+            answer: answer.to_string(),
+            too_high: entry.too_high.clone().unwrap_or_default(),
+        });
+    }
+    if bound(&entry.too_low).is_some_and(|too_low| value <= too_low) {
+        return Err(SubmitError::NotAbove {
+            answer: answer.to_string(),
+            too_low: entry.too_low.clone().unwrap_or_default(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Submits `answer` for `day` and `part` at `now`, records the outcome in
+/// the ledger and saves it.
+pub fn submit(
+    workspace: &Workspace,
+    ledger: &mut Ledger,
+    client: &Client,
+    (day, part): (u8, u8),
+    answer: &str,
+    now: SystemTime,
+) -> Result<Reply, SubmitError> {
+    match ledger.state(day, part) {
+        State::Expected(expected) => {
+            return Err(SubmitError::Solved {
+                expected: expected.to_string(),
+            })
+        }
+        State::WrongGuesses(wrong) if wrong.iter().any(|x| x == answer) => {
+            return Err(SubmitError::KnownWrong(answer.to_string()))
+        }
+        State::WrongGuesses(_) | State::Unknown => {}
+    }
+    if let Some(entry) = ledger.entry(day, part) {
+        check_bounds(entry, answer)?;
+    }
+
+    if let Some(wait) = Cooldown::load(workspace).remaining(now) {
+        return Err(SubmitError::Cooldown(wait));
+    }
+
+    let page = client.submit(day, part, answer)?;
+    let feedback =
+        parse_reply(&page).ok_or_else(|| SubmitError::UnknownReply(article_text(&page)))?;
+
+    if let Some(cooldown) = feedback.cooldown {
+        let until = seconds_since_epoch(now + cooldown);
+        Cooldown { until }.save(workspace)?;
+    }
+
+    let entry = ledger.entry_mut(day, part);
+    match feedback.reply {
+        Reply::Right => entry.expected = Some(answer.to_string()),
+        Reply::Wrong => entry.wrong.push(answer.to_string()),
+        // Having passed check_bounds, a number is inside any earlier bound.
+        Reply::TooHigh | Reply::TooLow => {
+            if numeric(answer).is_some() {
+                let bound = match feedback.reply {
+                    Reply::TooHigh => &mut entry.too_high,
+                    _ => &mut entry.too_low,
+                };
+                *bound = Some(answer.to_string());
+            }
+            entry.wrong.push(answer.to_string());
+        }
+        Reply::Wait(_) | Reply::AlreadySolved => return Ok(feedback.reply),
+    }
+    ledger.save(workspace)?;
+
+    Ok(feedback.reply)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::tests::stub_client;
+    use crate::http::tests::stub_server;
+    use crate::scaffold::tests::scratch_dir;
+    use rstest::*;
+
+    fn page(article: &str) -> String {
+        format!("<html><body><main><article><p>{article}</p></article></main></body></html>")
+    }
+
+    #[rstest]
+    #[case(
+        "That's the right answer!  You are <span class=\"day-success\">one gold star</span> closer.",
+        Reply::Right,
+        None
+    )]
+    #[case(
+        "That's not the right answer; your answer is too high.  If you're stuck, make sure you're using the full input data. <span>(You guessed <span style=\"white-space:nowrap;\"><code>99</code>.)</span></span> Please wait one minute before trying again.",
+        Reply::TooHigh,
+        Some(60)
+    )]
+    #[case(
+        "That's not the right answer; your answer is too low.  Because you have guessed incorrectly 5 times on this puzzle, please wait 5 minutes before trying again.",
+        Reply::TooLow,
+        Some(300)
+    )]
+    #[case(
+        "That's not the right answer.  If you're stuck, there are some general tips on the about page.",
+        Reply::Wrong,
+        None
+    )]
+    #[case(
+        "You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 23s left to wait.",
+        Reply::Wait(Duration::from_secs(83)),
+        Some(83)
+    )]
+    #[case(
+        "You don't seem to be solving the right level.  Did you already complete it?",
+        Reply::AlreadySolved,
+        None
+    )]
+    fn replies(#[case] article: &str, #[case] reply: Reply, #[case] cooldown: Option<u64>) {
+        assert_eq!(
+            parse_reply(&page(article)),
+            Some(Feedback {
+                reply,
+                cooldown: cooldown.map(Duration::from_secs)
+            })
+        );
+    }
+
+    #[test]
+    fn article_attributes_are_not_text() {
+        let html = r#"<main><article class="day-desc" title="That's the right answer"><p>That's not the right answer.</p></article></main>"#;
+
+        assert_eq!(article_text(html), "That's not the right answer.");
+        assert_eq!(
+            parse_reply(html).map(|feedback| feedback.reply),
+            Some(Reply::Wrong)
+        );
+    }
+
+    #[test]
+    fn unknown_reply() {
+        assert_eq!(parse_reply(&page("Something else entirely.")), None);
+    }
+
+    fn explode(input: &str) -> String {
+        panic!("no solution for {}", input.trim())
+    }
+
+    #[test]
+    fn panics_are_not_submitted() {
+        let workspace = Workspace::new(scratch_dir("submit-panic"));
+        let solution = Solution {
+            day: 1,
+            part: 1,
+            run: explode,
+            timed: |input| crate::registry::Timed::whole(input, explode),
+        };
+
+        let no_input = run_solution(&workspace, &solution);
+        fs::create_dir_all(workspace.day_dir(1)).unwrap();
+        fs::write(workspace.input_path(1), "42\n").unwrap();
+        let panicked = run_solution(&workspace, &solution);
+
+        assert!(matches!(no_input, Err(SubmitError::NoInput(_))));
+        assert!(
+            matches!(panicked, Err(SubmitError::Panicked(message)) if message == "no solution for 42")
+        );
+    }
+
+    #[test]
+    fn submissions_are_remembered() {
+        let workspace = Workspace::new(scratch_dir("submit"));
+        let (url, requests) = stub_server(vec![
+            (
+                200,
+                page("That's not the right answer; your answer is too high. Please wait one minute before trying again."),
+            ),
+            (200, page("That's the right answer!")),
+        ]);
+        let client = stub_client(&url);
+        let mut ledger = Ledger::default();
+        let start = UNIX_EPOCH + Duration::from_secs(1_000_000);
+
+        let first = submit(&workspace, &mut ledger, &client, (3, 1), "99", start);
+        let again = submit(&workspace, &mut ledger, &client, (3, 1), "99", start);
+        let too_soon = submit(&workspace, &mut ledger, &client, (3, 1), "42", start);
+        let later = start + Duration::from_secs(60);
+        let right = submit(&workspace, &mut ledger, &client, (3, 1), "42", later);
+        let solved = submit(&workspace, &mut ledger, &client, (3, 1), "42", later);
+
+        assert_eq!(first.unwrap(), Reply::TooHigh);
+        assert!(matches!(again, Err(SubmitError::KnownWrong(_))));
+        assert!(matches!(too_soon, Err(SubmitError::Cooldown(wait)) if wait.as_secs() == 60));
+        assert_eq!(right.unwrap(), Reply::Right);
+        assert!(matches!(solved, Err(SubmitError::Solved { .. })));
+
+        let received = requests.recv().unwrap();
+        assert_eq!(received.path, "/2023/day/3/answer");
+        assert_eq!(received.body, "level=1&answer=99");
+        assert_eq!(requests.recv().unwrap().body, "level=1&answer=42");
+        assert!(requests.try_recv().is_err());
+
+        let saved = Ledger::load(&workspace).unwrap();
+        let entry = saved.entry(3, 1).unwrap();
+        assert_eq!(entry.expected.as_deref(), Some("42"));
+        assert_eq!(entry.wrong, vec!["99".to_string()]);
+    }
+
+    #[test]
+    fn bounds_are_remembered() {
+        let workspace = Workspace::new(scratch_dir("submit-bounds"));
+        let too_high = page("That's not the right answer; your answer is too high.");
+        let too_low = page("That's not the right answer; your answer is too low.");
+        let (url, requests) = stub_server(vec![
+            (200, too_high.clone()),
+            (200, too_low),
+            (200, too_high),
+        ]);
+        let client = stub_client(&url);
+        let mut ledger = Ledger::default();
+        let now = UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let mut send = |answer| submit(&workspace, &mut ledger, &client, (3, 1), answer, now);
+
+        let replies = [send("99"), send("10"), send("50")];
+        let refused = [send("60"), send("50"), send("7"), send("10")];
+
+        assert!(replies
+            .iter()
+            .all(|reply| matches!(reply, Ok(Reply::TooHigh | Reply::TooLow))));
+        assert!(
+            matches!(&refused[0], Err(SubmitError::NotBelow { too_high, .. }) if too_high == "50")
+        );
+        assert!(matches!(&refused[1], Err(SubmitError::KnownWrong(_))));
+        assert!(
+            matches!(&refused[2], Err(SubmitError::NotAbove { too_low, .. }) if too_low == "10")
+        );
+        assert!(matches!(&refused[3], Err(SubmitError::KnownWrong(_))));
+
+        let sent: Vec<String> = requests.try_iter().map(|request| request.body).collect();
+        assert_eq!(
+            sent,
+            vec![
+                "level=1&answer=99",
+                "level=1&answer=10",
+                "level=1&answer=50"
+            ]
+        );
+
+        let saved = Ledger::load(&workspace).unwrap();
+        let entry = saved.entry(3, 1).unwrap();
+        assert_eq!(entry.too_high.as_deref(), Some("50"));
+        assert_eq!(entry.too_low.as_deref(), Some("10"));
+        assert_eq!(entry.wrong, vec!["99", "10", "50"]);
+    }
+}
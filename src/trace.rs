@@ -0,0 +1,202 @@
+//! Leveled debug output for the solutions, written to stderr.
+//!
+//! Nothing is printed unless a filter is installed, from `AOC_LOG` or the
+//! CLI's `--log`. A filter is a comma-separated list of `level` or
+//! `target=level`, where a target is a module path below the crate such as
+//! `day5` or `day5::pt2`; e.g. `AOC_LOG=warn,day12=trace`. The most specific
+//! target wins.
+//!
+//! `trace!(Debug, "{hand:?}")` costs one atomic load when its level is
+//! filtered out everywhere, and its arguments are not evaluated.
+
+use core::fmt;
+use std::env;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::RwLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error = 1,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        };
+
+        f.pad(name)
+    }
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "error" => Ok(Level::Error),
+            "warn" => Ok(Level::Warn),
+            "info" => Ok(Level::Info),
+            "debug" => Ok(Level::Debug),
+            "trace" => Ok(Level::Trace),
+            _ => Err(format!("unknown log level {s:?}")),
+        }
+    }
+}
+
+/// Which levels are shown for which targets. `None` shows nothing.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Filter {
+    default: Option<Level>,
+    targets: Vec<(String, Level)>,
+}
+
+impl FromStr for Filter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut filter = Filter::default();
+
+        for directive in s.split(',').map(str::trim).filter(|x| !x.is_empty()) {
+            match directive.split_once('=') {
+                Some((target, level)) => {
+                    filter.targets.push((target.to_string(), level.parse()?));
+                }
+                None => filter.default = Some(directive.parse()?),
+            }
+        }
+
+        // Longest first, so the first match is the most specific.
+        filter
+            .targets
+            .sort_by_key(|(target, _)| std::cmp::Reverse(target.len()));
+
+        Ok(filter)
+    }
+}
+
+impl Filter {
+    pub fn level(&self, target: &str) -> Option<Level> {
+        self.targets
+            .iter()
+            .find(|(prefix, _)| {
+                target == prefix
+                    || target
+                        .strip_prefix(prefix.as_str())
+                        .is_some_and(|rest| rest.starts_with("::"))
+            })
+            .map(|(_, level)| *level)
+            .or(self.default)
+    }
+
+    fn max_level(&self) -> Option<Level> {
+        self.targets
+            .iter()
+            .map(|(_, level)| *level)
+            .chain(self.default)
+            .max()
+    }
+}
+
+/// The most verbose level any target shows, 0 for none.
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(0);
+static FILTER: RwLock<Filter> = RwLock::new(Filter {
+    default: None,
+    targets: Vec::new(),
+});
+
+pub fn set_filter(filter: Filter) {
+    let max = filter.max_level().map_or(0, |level| level as u8);
+
+    *FILTER.write().unwrap() = filter;
+    MAX_LEVEL.store(max, Ordering::Relaxed);
+}
+
+/// Installs the filter in `AOC_LOG`, if there is one.
+pub fn init_from_env() -> Result<(), String> {
+    match env::var("AOC_LOG") {
+        Ok(spec) => {
+            set_filter(spec.parse()?);
+            Ok(())
+        }
+        Err(_) => Ok(()),
+    }
+}
+
+/// `advent_of_code_2023::day5::pt2` as `day5::pt2`.
+pub fn target(module_path: &'static str) -> &'static str {
+    module_path
+        .split_once("::")
+        .map_or(module_path, |(_, target)| target)
+}
+
+#[inline]
+pub fn enabled(level: Level, module_path: &'static str) -> bool {
+    level as u8 <= MAX_LEVEL.load(Ordering::Relaxed)
+        && FILTER
+            .read()
+            .unwrap()
+            .level(target(module_path))
+            .is_some_and(|max| level <= max)
+}
+
+pub fn emit(level: Level, module_path: &'static str, args: fmt::Arguments<'_>) {
+    eprintln!("[{level:<5} {}] {args}", target(module_path));
+}
+
+/// `trace!(Level, format, args...)` writes to stderr if the filter shows
+/// `Level` for the calling module.
+#[macro_export]
+macro_rules! trace {
+    ($level:ident, $($arg:tt)+) => {
+        if $crate::trace::enabled($crate::trace::Level::$level, module_path!()) {
+            $crate::trace::emit(
+                $crate::trace::Level::$level,
+                module_path!(),
+                format_args!($($arg)+),
+            );
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("", "day5::pt2", None)]
+    #[case("debug", "day5::pt2", Some(Level::Debug))]
+    #[case("warn,day5=trace", "day5::pt2", Some(Level::Trace))]
+    #[case("warn,day5=trace", "day12::pt1", Some(Level::Warn))]
+    #[case("day5=trace,day5::pt1=error", "day5::pt1", Some(Level::Error))]
+    #[case("day1=info", "day12::pt1", None)]
+    #[case("day1=info", "day1", Some(Level::Info))]
+    fn filter_level(#[case] spec: &str, #[case] target: &str, #[case] expected: Option<Level>) {
+        let filter: Filter = spec.parse().unwrap();
+
+        assert_eq!(filter.level(target), expected);
+    }
+
+    #[rstest]
+    #[case("loud")]
+    #[case("day5=loud")]
+    fn bad_filter(#[case] spec: &str) {
+        assert!(spec.parse::<Filter>().is_err());
+    }
+
+    #[test]
+    fn targets() {
+        assert_eq!(target("advent_of_code_2023::day5::pt2"), "day5::pt2");
+        assert_eq!(target("advent_of_code_2023"), "advent_of_code_2023");
+    }
+}
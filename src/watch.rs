@@ -0,0 +1,175 @@
+//! `aoc watch <day>`: reruns `aoc check <day>` whenever something in the
+//! day's directory or its input changes. The check runs through `cargo run`,
+//! so it sees the code as it is now rather than as this binary was built.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::workspace::Workspace;
+
+pub const DEFAULT_INTERVAL: Duration = Duration::from_millis(500);
+
+/// When every file below a directory was last modified.
+pub type Snapshot = BTreeMap<PathBuf, SystemTime>;
+
+pub fn snapshot(dir: &Path) -> Snapshot {
+    let mut files = Snapshot::new();
+    let mut dirs = vec![dir.to_path_buf()];
+
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            match entry.metadata() {
+                Ok(metadata) if metadata.is_dir() => dirs.push(path),
+                Ok(metadata) => {
+                    let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                    files.insert(path, modified);
+                }
+                Err(_) => {}
+            }
+        }
+    }
+
+    files
+}
+
+/// The day's directory and its input, which may be the downloaded one in
+/// `inputs/`.
+pub fn day_snapshot(workspace: &Workspace, day: u8) -> Snapshot {
+    let mut files = snapshot(&workspace.day_dir(day));
+
+    let input = workspace.input_path(day);
+    if let Ok(metadata) = fs::metadata(&input) {
+        let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+        files.insert(input, modified);
+    }
+
+    files
+}
+
+/// Files added, removed or modified between two snapshots.
+pub fn changes(before: &Snapshot, after: &Snapshot) -> Vec<PathBuf> {
+    let mut changed: Vec<PathBuf> = after
+        .iter()
+        .filter(|(path, modified)| before.get(*path) != Some(modified))
+        .map(|(path, _)| path.clone())
+        .chain(
+            before
+                .keys()
+                .filter(|path| !after.contains_key(*path))
+                .cloned(),
+        )
+        .collect();
+    changed.sort();
+    changed
+}
+
+/// `cargo run -- check <day>` in the workspace.
+pub fn run_check(workspace: &Workspace, day: u8) -> std::io::Result<ExitStatus> {
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+
+    Command::new(cargo)
+        .args(["run", "--quiet", "--bin", "aoc", "--", "check"])
+        .arg(day.to_string())
+        .current_dir(workspace.root())
+        .status()
+}
+
+/// Calls `on_change` with the changed files, first with none, then every
+/// time the day's directory or input changes, checking every `interval`.
+/// Never returns.
+pub fn watch(
+    workspace: &Workspace,
+    day: u8,
+    interval: Duration,
+    mut on_change: impl FnMut(&[PathBuf]),
+) -> ! {
+    let mut before = day_snapshot(workspace, day);
+    on_change(&[]);
+
+    loop {
+        thread::sleep(interval);
+
+        let after = day_snapshot(workspace, day);
+        let changed = changes(&before, &after);
+        if !changed.is_empty() {
+            on_change(&changed);
+            // Whatever changed while the check ran counts as seen by it.
+            before = day_snapshot(workspace, day);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scaffold::tests::scratch_dir;
+
+    #[test]
+    fn detects_changes() {
+        let dir = scratch_dir("watch");
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        fs::write(dir.join("pt1.rs"), "// one").unwrap();
+        fs::write(dir.join("input.txt"), "1").unwrap();
+        let before = snapshot(&dir);
+
+        let modified = SystemTime::now() + Duration::from_secs(5);
+        fs::File::options()
+            .write(true)
+            .open(dir.join("pt1.rs"))
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+        fs::remove_file(dir.join("input.txt")).unwrap();
+        fs::write(dir.join("nested").join("new.rs"), "").unwrap();
+        let after = snapshot(&dir);
+
+        assert_eq!(
+            changes(&before, &after),
+            vec![
+                dir.join("input.txt"),
+                dir.join("nested").join("new.rs"),
+                dir.join("pt1.rs"),
+            ]
+        );
+        assert!(changes(&after, &after).is_empty());
+    }
+
+    #[test]
+    fn watches_downloaded_input() {
+        let workspace = Workspace::new(scratch_dir("watch-input"));
+        fs::create_dir_all(workspace.day_dir(3)).unwrap();
+        fs::write(workspace.day_dir(3).join("pt1.rs"), "").unwrap();
+        let before = day_snapshot(&workspace, 3);
+
+        fs::create_dir_all(workspace.inputs_dir()).unwrap();
+        fs::write(workspace.cached_input_path(3), "1").unwrap();
+        let fetched = day_snapshot(&workspace, 3);
+
+        assert_eq!(
+            changes(&before, &fetched),
+            vec![workspace.cached_input_path(3)]
+        );
+
+        let modified = SystemTime::now() + Duration::from_secs(5);
+        fs::File::options()
+            .write(true)
+            .open(workspace.cached_input_path(3))
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+
+        assert_eq!(
+            changes(&fetched, &day_snapshot(&workspace, 3)),
+            vec![workspace.cached_input_path(3)]
+        );
+    }
+}
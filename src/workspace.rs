@@ -0,0 +1,105 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Where the repository lives on disk. Tools that read or write day modules,
+/// inputs or answers resolve their paths through it, so tests can point it at
+/// a scratch directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Workspace {
+    root: PathBuf,
+}
+
+impl Workspace {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Workspace { root: root.into() }
+    }
+
+    /// `AOC_ROOT` if set, otherwise the checkout this binary was built from.
+    pub fn from_env() -> Self {
+        match env::var_os("AOC_ROOT") {
+            Some(root) => Workspace::new(root),
+            None => Workspace::new(env!("CARGO_MANIFEST_DIR")),
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn src_dir(&self) -> PathBuf {
+        self.root.join("src")
+    }
+
+    pub fn day_dir(&self, day: u8) -> PathBuf {
+        self.src_dir().join(format!("day{day}"))
+    }
+
+    pub fn template_dir(&self) -> PathBuf {
+        self.day_dir(0)
+    }
+
+    pub fn lib_rs(&self) -> PathBuf {
+        self.src_dir().join("lib.rs")
+    }
+
+    pub fn registry_rs(&self) -> PathBuf {
+        self.src_dir().join("registry.rs")
+    }
+
+    pub fn answers_path(&self) -> PathBuf {
+        self.root.join("answers.toml")
+    }
+
+    /// Session token and server for `aoc fetch`; not committed.
+    pub fn config_path(&self) -> PathBuf {
+        self.root.join("aoc.toml")
+    }
+
+    /// Where `aoc bench` writes its results unless told otherwise.
+    pub fn bench_results_path(&self) -> PathBuf {
+        self.root.join("target").join("bench").join("results.toml")
+    }
+
+    /// When the puzzle server will take another answer, kept by `aoc submit`.
+    pub fn cooldown_path(&self) -> PathBuf {
+        self.root.join("target").join("aoc").join("cooldown.toml")
+    }
+
+    /// Inputs downloaded by `aoc fetch`; not committed.
+    pub fn inputs_dir(&self) -> PathBuf {
+        self.root.join("inputs")
+    }
+
+    pub fn cached_input_path(&self, day: u8) -> PathBuf {
+        self.inputs_dir().join(format!("day{day}.txt"))
+    }
+
+    /// The day's input: its committed `input.txt` if that has anything in it,
+    /// else the downloaded one if there is one, else where `input.txt` goes.
+    pub fn input_path(&self, day: u8) -> PathBuf {
+        let committed = self.day_dir(day).join("input.txt");
+        let cached = self.cached_input_path(day);
+
+        let has_input = fs::metadata(&committed).is_ok_and(|metadata| metadata.len() > 0);
+        if !has_input && cached.exists() {
+            cached
+        } else {
+            committed
+        }
+    }
+
+    /// The day's puzzle text, `README.md` if there is one, else `README.txt`.
+    pub fn readme_path(&self, day: u8) -> PathBuf {
+        let markdown = self.day_dir(day).join("README.md");
+        if markdown.exists() {
+            markdown
+        } else {
+            self.day_dir(day).join("README.txt")
+        }
+    }
+
+    pub fn examples_path(&self, day: u8) -> PathBuf {
+        self.day_dir(day).join("examples.toml")
+    }
+}